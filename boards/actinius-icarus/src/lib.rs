@@ -16,6 +16,7 @@ pub mod prelude {
 }
 
 use hal::{
+    clocks::Clocks,
     gpio::{p0, Floating, Input, Level, Output, Pin, PullUp, PushPull},
     pac::{CorePeripherals, Peripherals},
     uarte::{self, Baudrate as UartBaudrate, Parity as UartParity, Uarte},
@@ -430,10 +431,17 @@ impl Board {
     fn new(cp: CorePeripherals, p: Peripherals) -> Self {
         let pins0 = p0::Parts::new(p.P0_NS);
 
+        // The UARTE needs the external high frequency oscillator for an
+        // accurate baud rate. It only runs while the UARTE is transferring,
+        // the rest of the clock configuration is left to the application.
+        let clocks = Clocks::new(p.CLOCK_NS);
+        let hfxo = clocks.hfxo_requester();
+        let clock = clocks.free();
+
         // The Actinius Icarus features an USB CDC port. It features HWFC but
         // does not have to use it. It can transmit a flexible baudrate of up
         // to 1Mbps.
-        let cdc_uart = Uarte::new(
+        let cdc_uart = Uarte::with_hfxo_requester(
             p.UARTE0_NS,
             uarte::Pins {
                 txd: pins0.p0_09.into_push_pull_output(Level::High).degrade(),
@@ -443,11 +451,12 @@ impl Board {
             },
             UartParity::EXCLUDED,
             UartBaudrate::BAUD115200,
+            hfxo,
         );
 
         // The Actinius Icarus also features a UART mapped out to edge pins 23
         // and 24.
-        let pin_uart = Uarte::new(
+        let pin_uart = Uarte::with_hfxo_requester(
             p.UARTE1_NS,
             uarte::Pins {
                 txd: pins0.p0_23.into_push_pull_output(Level::High).degrade(),
@@ -457,6 +466,7 @@ impl Board {
             },
             UartParity::EXCLUDED,
             UartBaudrate::BAUD115200,
+            hfxo,
         );
 
         Board {
@@ -527,7 +537,7 @@ impl Board {
             TPIU: cp.TPIU,
 
             // nRF9160 non-secure peripherals
-            CLOCK_NS: p.CLOCK_NS,
+            CLOCK_NS: clock,
             DPPIC_NS: p.DPPIC_NS,
             EGU0_NS: p.EGU0_NS,
             EGU1_NS: p.EGU1_NS,
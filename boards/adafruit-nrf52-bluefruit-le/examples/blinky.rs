@@ -63,11 +63,11 @@ fn main() -> ! {
     }
 }
 
-fn delay<T>(timer: &mut Timer<T>, cycles: u32)
+fn delay<T>(timer: &mut Timer<T>, micros: u32)
 where
     T: timer::Instance,
 {
-    timer.start(cycles);
+    timer.start(micros.us());
     block!(timer.wait()).unwrap();
 }
 
@@ -5,6 +5,7 @@ pub mod prelude {
 }
 
 use nrf52832_hal::{
+    clocks::Clocks,
     gpio::{p0, Floating, Input, Level, Output, Pin, PushPull},
     target::{self as pac, CorePeripherals, Peripherals},
     uarte, Uarte,
@@ -242,7 +243,14 @@ impl Board {
     pub fn new(cp: CorePeripherals, p: Peripherals) -> Self {
         let pins = p0::Parts::new(p.P0);
 
-        let cdc_uarte = Uarte::new(
+        // The UARTE needs the external high frequency oscillator for an
+        // accurate baud rate. It only runs while the UARTE is transferring,
+        // the rest of the clock configuration is left to the application.
+        let clocks = Clocks::new(p.CLOCK);
+        let hfxo = clocks.hfxo_requester();
+        let clock = clocks.free();
+
+        let cdc_uarte = Uarte::with_hfxo_requester(
             p.UARTE0,
             uarte::Pins {
                 txd: pins.p0_06.into_push_pull_output(Level::High).degrade(),
@@ -252,6 +260,7 @@ impl Board {
             },
             uarte::Parity::EXCLUDED,
             uarte::Baudrate::BAUD115200,
+            hfxo,
         );
 
         Self {
@@ -307,7 +316,7 @@ impl Board {
             FICR: p.FICR,
             UICR: p.UICR,
             POWER: p.POWER,
-            CLOCK: p.CLOCK,
+            CLOCK: clock,
             RADIO: p.RADIO,
 
             UART0: p.UART0,
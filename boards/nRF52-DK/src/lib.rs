@@ -14,6 +14,7 @@ pub mod prelude {
 }
 
 use nrf52832_hal::{
+    clocks::Clocks,
     gpio::{
         p0,
         Pin,
@@ -298,10 +299,17 @@ impl Board {
     fn new(cp: CorePeripherals, p: Peripherals) -> Self {
         let pins0 = p0::Parts::new(p.P0);
 
+        // The UARTE needs the external high frequency oscillator for an
+        // accurate baud rate. It only runs while the UARTE is transferring,
+        // the rest of the clock configuration is left to the application.
+        let clocks = Clocks::new(p.CLOCK);
+        let hfxo = clocks.hfxo_requester();
+        let clock = clocks.free();
+
         // The nRF52-DK features an USB CDC port.
         // It features HWFC but does not have to use it.
         // It can transmit a flexible baudrate of up to 1Mbps.
-        let cdc_uart = Uarte::new(
+        let cdc_uart = Uarte::with_hfxo_requester(
                 p.UARTE0,
                 uarte::Pins {
                 txd: pins0.p0_06.into_push_pull_output(Level::High).degrade(),
@@ -310,7 +318,8 @@ impl Board {
                 rts: Some(pins0.p0_05.into_push_pull_output(Level::High).degrade()),
             },
             UartParity::EXCLUDED,
-            UartBaudrate::BAUD115200
+            UartBaudrate::BAUD115200,
+            hfxo,
         );
 
         Board {
@@ -373,7 +382,7 @@ impl Board {
             FICR  : p.FICR,
             UICR  : p.UICR,
             POWER : p.POWER,
-            CLOCK : p.CLOCK,
+            CLOCK : clock,
             RADIO : p.RADIO,
 
             UART0 : p.UART0,
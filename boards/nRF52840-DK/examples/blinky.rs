@@ -31,10 +31,10 @@ fn main() -> ! {
     }
 }
 
-fn delay<T>(timer: &mut Timer<T>, cycles: u32)
+fn delay<T>(timer: &mut Timer<T>, micros: u32)
 where
     T: timer::Instance,
 {
-    timer.start(cycles);
+    timer.start(micros.us());
     let _ = block!(timer.wait());
 }
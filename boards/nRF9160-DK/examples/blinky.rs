@@ -25,7 +25,7 @@ fn main() -> ! {
         } else {
             board.leds.led_1.enable();
         }
-        timer.start(1_000_000_u32);
+        timer.start(1.s());
         block!(timer.wait()).unwrap();
         led_is_on = !led_is_on;
     }
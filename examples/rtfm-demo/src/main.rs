@@ -16,20 +16,57 @@ use nrf52832_hal as hal;
 #[cfg(feature = "52840")]
 use nrf52840_hal as hal;
 
+use hal::target::TIMER1;
+use hal::timer::{Duration, MonotonicTimer};
 
-#[app(device = crate::hal::target)]
+/// The clock used for scheduling tasks, in place of the DWT cycle counter
+type Mono = MonotonicTimer<TIMER1>;
+
+const PERIOD: Duration = Duration::from_secs(1);
+
+
+#[app(device = crate::hal::target, peripherals = true, monotonic = crate::Mono)]
 const APP: () = {
-    #[init]
-    fn init() {
+    #[init(schedule = [tick])]
+    fn init(cx: init::Context) {
         hprintln!("init").unwrap();
+
+        // The clock keeps running after the handle is dropped. RTFM resets it
+        // to zero once `init` returns.
+        Mono::new(cx.device.TIMER1);
+
+        cx.schedule.tick(cx.start + PERIOD).unwrap();
     }
 
     #[idle]
-    fn idle() -> ! {
+    fn idle(_: idle::Context) -> ! {
         hprintln!("idle").unwrap();
 
-        debug::exit(debug::EXIT_SUCCESS);
+        loop {
+            cortex_m::asm::wfi();
+        }
+    }
+
+    #[task(schedule = [tick])]
+    fn tick(cx: tick::Context) {
+        static mut COUNT: u32 = 0;
+
+        hprintln!("tick at {} us", Mono::now().as_micros()).unwrap();
+
+        *COUNT += 1;
+        if *COUNT == 3 {
+            debug::exit(debug::EXIT_SUCCESS);
+        }
+
+        cx.schedule.tick(cx.scheduled + PERIOD).unwrap();
+    }
+
+    #[task(binds = TIMER1)]
+    fn timer1(_: timer1::Context) {
+        Mono::on_interrupt();
+    }
 
-        loop {}
+    extern "C" {
+        fn SWI0_EGU0();
     }
 };
@@ -0,0 +1,117 @@
+#![no_std]
+#![no_main]
+
+extern crate panic_halt;
+
+use core::cell::RefCell;
+
+use cortex_m::interrupt::Mutex;
+use cortex_m_rt::entry;
+use embedded_hal::digital::v2::OutputPin;
+use nrf52832_hal::{
+    gpio::{p0, Level, Output, Pin, PushPull},
+    nrf52832_pac::{self as pac, interrupt},
+    rtc::{Rtc, RtcInterrupt},
+    saadc::{ChannelConfig, Continuous, Limit, SampleTrigger, Saadc, SaadcConfig},
+    Clocks,
+};
+
+struct State {
+    saadc: Continuous,
+    led1: Pin<Output<PushPull>>,
+    led2: Pin<Output<PushPull>>,
+}
+
+static STATE: Mutex<RefCell<Option<State>>> = Mutex::new(RefCell::new(None));
+
+static mut BUF_A: [i16; 64] = [0; 64];
+static mut BUF_B: [i16; 64] = [0; 64];
+
+// With the default configuration (gain 1/4, reference VDD/4, 14 bit), a
+// 3.0 V supply reads as 16384.
+const LIMIT_LOW: i16 = (500 * 16384 / 3000) as i16;
+const LIMIT_HIGH: i16 = (2500 * 16384 / 3000) as i16;
+
+/// SAADC limit event demonstration code.
+///
+/// The RTC triggers a sample eight times per second through PPI. The CPU is
+/// only woken when a sample leaves the window between `LIMIT_LOW` and
+/// `LIMIT_HIGH`, or every 8 seconds to recycle the sample buffers.
+#[entry]
+fn main() -> ! {
+    let p = pac::Peripherals::take().unwrap();
+    let mut cp = pac::CorePeripherals::take().unwrap();
+    let port0 = p0::Parts::new(p.P0);
+
+    let led1 = port0.p0_17.into_push_pull_output(Level::High).degrade();
+    let led2 = port0.p0_18.into_push_pull_output(Level::High).degrade();
+    let mut ain1 = port0.p0_03.into_floating_input();
+
+    // The RTC runs from the low frequency clock
+    let clocks = Clocks::new(p.CLOCK)
+        .set_lfclk_src_rc()
+        .start_lfclk()
+        .freeze();
+
+    // 32_768 Hz / (4095 + 1) = 8 Hz
+    let rtc_tick = &p.RTC0.events_tick as *const _ as u32;
+    let mut rtc = Rtc::new(p.RTC0, clocks);
+    rtc.set_prescaler(4095).unwrap();
+    rtc.enable_event(RtcInterrupt::Tick);
+    let _rtc = rtc.enable_counter();
+
+    let mut saadc = Saadc::new(p.SAADC, SaadcConfig::default()).unwrap();
+    saadc.enable_channel(0, &mut ain1, ChannelConfig::default());
+    saadc.set_limits(0, LIMIT_LOW, LIMIT_HIGH);
+    saadc.enable_limit_interrupt(0, Limit::High, None);
+    saadc.enable_limit_interrupt(0, Limit::Low, None);
+
+    let mut saadc = saadc
+        .continuous(
+            SampleTrigger::External,
+            unsafe { &mut BUF_A },
+            unsafe { &mut BUF_B },
+        )
+        .ok()
+        .expect("Invalid SAADC buffers");
+    saadc.enable_interrupt(Some(&mut cp.NVIC));
+
+    // Connect the RTC TICK event to the SAADC SAMPLE task
+    let sample_task = saadc.tasks_sample_address();
+    p.PPI.ch[0].eep.write(|w| unsafe { w.bits(rtc_tick) });
+    p.PPI.ch[0].tep.write(|w| unsafe { w.bits(sample_task) });
+    p.PPI.chenset.write(|w| w.ch0().set());
+
+    cortex_m::interrupt::free(|cs| {
+        *STATE.borrow(cs).borrow_mut() = Some(State { saadc, led1, led2 });
+    });
+
+    loop {
+        cortex_m::asm::wfi();
+    }
+}
+
+#[interrupt]
+fn SAADC() {
+    cortex_m::interrupt::free(|cs| {
+        if let Some(ref mut state) = *STATE.borrow(cs).borrow_mut() {
+            // Only the limits are of interest, so keep the buffers going
+            // around
+            if let Some(buf) = state.saadc.take_completed() {
+                state.saadc.give_next(buf).ok();
+            }
+
+            // The LEDs are active low
+            if state.saadc.is_limit_triggered(0, Limit::High) {
+                state.saadc.clear_limit_event(0, Limit::High);
+                state.led1.set_low().unwrap();
+                state.led2.set_high().unwrap();
+            }
+            if state.saadc.is_limit_triggered(0, Limit::Low) {
+                state.saadc.clear_limit_event(0, Limit::Low);
+                state.led1.set_high().unwrap();
+                state.led2.set_low().unwrap();
+            }
+        }
+    });
+}
@@ -2,10 +2,23 @@
 //! sources
 
 #[cfg(feature="9160")]
-use crate::target::CLOCK_NS as CLOCK;
+use crate::target::{CLOCK_NS as CLOCK, Interrupt, NVIC};
 
 #[cfg(not(feature="9160"))]
-use crate::target::CLOCK;
+use crate::target::{CLOCK, Interrupt, NVIC};
+
+use core::cell::Cell;
+use core::marker::PhantomData;
+
+use cortex_m::interrupt::{self, Mutex};
+
+use crate::wait::wait_for_event;
+
+#[cfg(feature="9160")]
+const INTERRUPT: Interrupt = Interrupt::CLOCK_POWER;
+
+#[cfg(not(feature="9160"))]
+const INTERRUPT: Interrupt = Interrupt::POWER_CLOCK;
 
 // ZST Type States
 
@@ -15,12 +28,18 @@ pub struct Internal;
 /// External Crystal Oscillator
 pub struct ExternalOscillator;
 
+/// External Crystal Oscillator, requested but possibly not running yet
+pub struct HfxoStarting;
+
 /// Low Frequency Clock synthesize from High Frequency Clock
 pub struct LfOscSynthesized;
 
 /// Low Frequency Clock Started
 pub struct LfOscStarted;
 
+/// Low Frequency Clock started, but possibly not running yet
+pub struct LfOscStarting;
+
 /// Low Frequency Clock Stopped
 pub struct LfOscStopped;
 
@@ -29,6 +48,65 @@ pub const HFCLK_FREQ: u32 = 64_000_000;
 /// Low Frequency Clock Frequency (in Hz)
 pub const LFCLK_FREQ: u32 = 32_768;
 
+/// Source of the high frequency clock
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HfclkSource {
+    Internal,
+    ExternalOscillator,
+}
+
+/// Source of the low frequency clock
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LfclkSource {
+    Internal,
+    ExternalOscillator,
+    #[cfg(not(feature = "9160"))]
+    Synthesized,
+}
+
+/// Events generated by the CLOCK peripheral
+pub enum ClocksEvent {
+    HfclkStarted,
+    LfclkStarted,
+    /// LFRC calibration is done
+    #[cfg(not(feature = "9160"))]
+    Done,
+    /// The calibration timer has timed out
+    #[cfg(not(feature = "9160"))]
+    CalibrationTimeout,
+}
+
+/// Implemented by the type states of the high frequency clock
+pub trait HfclkType {
+    const SOURCE: HfclkSource;
+}
+
+impl HfclkType for Internal {
+    const SOURCE: HfclkSource = HfclkSource::Internal;
+}
+
+impl HfclkType for ExternalOscillator {
+    const SOURCE: HfclkSource = HfclkSource::ExternalOscillator;
+}
+
+/// Implemented by the type states of the low frequency clock
+pub trait LfclkType {
+    const SOURCE: LfclkSource;
+}
+
+impl LfclkType for Internal {
+    const SOURCE: LfclkSource = LfclkSource::Internal;
+}
+
+impl LfclkType for ExternalOscillator {
+    const SOURCE: LfclkSource = LfclkSource::ExternalOscillator;
+}
+
+#[cfg(not(feature = "9160"))]
+impl LfclkType for LfOscSynthesized {
+    const SOURCE: LfclkSource = LfclkSource::Synthesized;
+}
+
 /// A high level abstraction for the CLOCK peripheral
 pub struct Clocks<H, L, LSTAT> {
     hfclk: H,
@@ -50,14 +128,20 @@ impl Clocks<Internal, Internal, LfOscStopped> {
 
 impl<H, L, LSTAT> Clocks<H, L, LSTAT> {
     /// Use an external oscillator as the high frequency clock source
+    ///
+    /// The CLOCK interrupt handler must not clear the `HfclkStarted` event
+    /// while this sleeps until the oscillator is running.
     pub fn enable_ext_hfosc(self) -> Clocks<ExternalOscillator, L, LSTAT> {
+        interrupt::free(|cs| HFXO_USERS.borrow(cs).set_clocks(true));
+        self.periph.events_hfclkstarted.reset();
         self.periph.tasks_hfclkstart.write(|w| unsafe { w.bits(1) });
 
         // Datasheet says this is likely to take 0.36ms
-        while self.periph.events_hfclkstarted.read().bits() != 1 {}
-        self.periph
-            .events_hfclkstarted
-            .write(|w| unsafe { w.bits(0) });
+        let periph = &self.periph;
+        wait_for_event(periph, INTERRUPT, 1 << 0, || {
+            periph.events_hfclkstarted.read().bits() != 0
+        });
+        self.periph.events_hfclkstarted.reset();
 
         Clocks {
             hfclk: ExternalOscillator,
@@ -67,9 +151,37 @@ impl<H, L, LSTAT> Clocks<H, L, LSTAT> {
         }
     }
 
+    /// Use an external oscillator as the high frequency clock source, without
+    /// waiting for it to start
+    ///
+    /// The `HfclkStarted` event signals when the oscillator is running. Use
+    /// `is_hfxo_running` or `wait_hfxo` to get to the `ExternalOscillator`
+    /// state.
+    pub fn enable_ext_hfosc_nowait(self) -> Clocks<HfxoStarting, L, LSTAT> {
+        interrupt::free(|cs| HFXO_USERS.borrow(cs).set_clocks(true));
+        self.periph.events_hfclkstarted.reset();
+        self.periph.tasks_hfclkstart.write(|w| unsafe { w.bits(1) });
+
+        Clocks {
+            hfclk: HfxoStarting,
+            lfclk: self.lfclk,
+            lfstat: self.lfstat,
+            periph: self.periph,
+        }
+    }
+
     /// Use the internal oscillator as the high frequency clock source
+    ///
+    /// The external oscillator keeps running while there are outstanding
+    /// `HfxoRequest`s.
     pub fn disable_ext_hfosc(self) -> Clocks<Internal, L, LSTAT> {
-        self.periph.tasks_hfclkstop.write(|w| unsafe { w.bits(1) });
+        interrupt::free(|cs| {
+            let users = HFXO_USERS.borrow(cs);
+            users.set_clocks(false);
+            if !users.any() {
+                self.periph.tasks_hfclkstop.write(|w| unsafe { w.bits(1) });
+            }
+        });
         Clocks {
             hfclk: Internal,
             lfclk: self.lfclk,
@@ -79,15 +191,20 @@ impl<H, L, LSTAT> Clocks<H, L, LSTAT> {
     }
 
     /// Start the Low Frequency clock
+    ///
+    /// The CLOCK interrupt handler must not clear the `LfclkStarted` event
+    /// while this sleeps until the clock is running.
     pub fn start_lfclk(self) -> Clocks<H, L, LfOscStarted> {
+        self.periph.events_lfclkstarted.reset();
         self.periph.tasks_lfclkstart.write(|w| unsafe { w.bits(1) });
 
         // Datasheet says this could take 100us from synth source
         // 600us from rc source, 0.25s from an external source
-        while self.periph.events_lfclkstarted.read().bits() != 1 {}
-        self.periph
-            .events_lfclkstarted
-            .write(|w| unsafe { w.bits(0) });
+        let periph = &self.periph;
+        wait_for_event(periph, INTERRUPT, 1 << 1, || {
+            periph.events_lfclkstarted.read().bits() != 0
+        });
+        self.periph.events_lfclkstarted.reset();
 
         Clocks {
             hfclk: self.hfclk,
@@ -96,6 +213,342 @@ impl<H, L, LSTAT> Clocks<H, L, LSTAT> {
             periph: self.periph,
         }
     }
+
+    /// Freeze the clock configuration
+    ///
+    /// Drivers that depend on a clock take the returned token, whose type
+    /// proves the clock is set up accordingly. The configuration can't be
+    /// changed anymore afterwards.
+    pub fn freeze(self) -> Frozen<H, L, LSTAT> {
+        Frozen {
+            lfclk_accuracy_ppm: DEFAULT_LFCLK_ACCURACY_PPM,
+            _config: PhantomData,
+        }
+    }
+
+    /// Get a handle for requesting the external high frequency oscillator
+    ///
+    /// The handle can be copied and passed to any driver that needs the
+    /// oscillator.
+    pub fn hfxo_requester(&self) -> HfxoRequester {
+        HfxoRequester { _private: () }
+    }
+
+    /// Return the raw interface to the underlying CLOCK peripheral
+    ///
+    /// The clocks keep running as configured. Outstanding `HfxoRequest`s
+    /// remain valid, and a later `Clocks::new` takes them into account.
+    pub fn free(self) -> CLOCK {
+        self.periph
+    }
+
+    /// Start the Low Frequency clock, without waiting for it to start
+    ///
+    /// The `LfclkStarted` event signals when the clock is running. Use
+    /// `lfclk_running` or `wait_lfclk` to get to the `LfOscStarted` state.
+    pub fn start_lfclk_nowait(self) -> Clocks<H, L, LfOscStarting> {
+        self.periph.events_lfclkstarted.reset();
+        self.periph.tasks_lfclkstart.write(|w| unsafe { w.bits(1) });
+
+        Clocks {
+            hfclk: self.hfclk,
+            lfclk: self.lfclk,
+            lfstat: LfOscStarting,
+            periph: self.periph,
+        }
+    }
+
+    /// The source the high frequency clock is running from
+    pub fn hfclk_source(&self) -> HfclkSource {
+        if self.periph.hfclkstat.read().bits() & 1 == 0 {
+            HfclkSource::Internal
+        } else {
+            HfclkSource::ExternalOscillator
+        }
+    }
+
+    /// Whether the high frequency clock is running
+    pub fn hfclk_running(&self) -> bool {
+        self.periph.hfclkstat.read().bits() & (1 << 16) != 0
+    }
+
+    /// The source the low frequency clock is running from, if it has been
+    /// started
+    pub fn lfclk_source(&self) -> Option<LfclkSource> {
+        let stat = self.periph.lfclkstat.read().bits();
+        if stat & (1 << 16) == 0 {
+            return None;
+        }
+
+        lfclk_source_from_bits(stat & 0b11)
+    }
+
+    /// Whether the low frequency clock is running
+    pub fn lfclk_running(&self) -> bool {
+        self.lfclk_source().is_some()
+    }
+
+    /// Enable the generation of a hardware interrupt from a given event
+    ///
+    /// If access to the NVIC is not provided, the interrupt must ALSO be enabled
+    /// there outside of this function (e.g. manually call `nvic.enable`, or through
+    /// the use of RTFM).
+    pub fn enable_interrupt(&mut self, event: ClocksEvent, nvic: Option<&mut NVIC>) {
+        self.periph
+            .intenset
+            .write(|w| unsafe { w.bits(event_mask(&event)) });
+
+        if let Some(_nvic) = nvic {
+            unsafe { NVIC::unmask(INTERRUPT) };
+        }
+    }
+
+    /// Disable the generation of a hardware interrupt from a given event
+    ///
+    /// If access to the NVIC is not provided, the interrupt must ALSO be disabled
+    /// there outside of this function (e.g. manually call `nvic.disable`, or through
+    /// the use of RTFM).
+    pub fn disable_interrupt(&mut self, event: ClocksEvent, nvic: Option<&mut NVIC>) {
+        self.periph
+            .intenclr
+            .write(|w| unsafe { w.bits(event_mask(&event)) });
+
+        if let Some(_nvic) = nvic {
+            NVIC::mask(INTERRUPT);
+        }
+    }
+
+    /// Whether the given event has occurred
+    pub fn is_event_triggered(&self, event: ClocksEvent) -> bool {
+        match event {
+            ClocksEvent::HfclkStarted => self.periph.events_hfclkstarted.read().bits() != 0,
+            ClocksEvent::LfclkStarted => self.periph.events_lfclkstarted.read().bits() != 0,
+            #[cfg(not(feature = "9160"))]
+            ClocksEvent::Done => self.periph.events_done.read().bits() != 0,
+            #[cfg(not(feature = "9160"))]
+            ClocksEvent::CalibrationTimeout => self.periph.events_ctto.read().bits() != 0,
+        }
+    }
+
+    /// Clear the given event
+    pub fn reset_event(&mut self, event: ClocksEvent) {
+        match event {
+            ClocksEvent::HfclkStarted => self.periph.events_hfclkstarted.reset(),
+            ClocksEvent::LfclkStarted => self.periph.events_lfclkstarted.reset(),
+            #[cfg(not(feature = "9160"))]
+            ClocksEvent::Done => self.periph.events_done.reset(),
+            #[cfg(not(feature = "9160"))]
+            ClocksEvent::CalibrationTimeout => self.periph.events_ctto.reset(),
+        }
+    }
+}
+
+/// The LFCLK accuracy required by BLE
+const DEFAULT_LFCLK_ACCURACY_PPM: u16 = 500;
+
+/// The frozen clock configuration
+///
+/// Produced by `Clocks::freeze` and taken by drivers that depend on a clock,
+/// e.g. `Rtc::new` requires the low frequency clock to be started and
+/// `Uarte::new` requires the external high frequency oscillator for an
+/// accurate baud rate.
+///
+/// `Delay` and `Timer` don't take the token: the CPU and the timers run at
+/// `HFCLK_FREQ` and 16 MHz from either high frequency source, the external
+/// oscillator only improves their accuracy.
+pub struct Frozen<H, L, LSTAT> {
+    lfclk_accuracy_ppm: u16,
+    _config: PhantomData<(H, L, LSTAT)>,
+}
+
+impl<H, L, LSTAT> Clone for Frozen<H, L, LSTAT> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<H, L, LSTAT> Copy for Frozen<H, L, LSTAT> {}
+
+impl<H, L, LSTAT> Frozen<H, L, LSTAT>
+where
+    H: HfclkType,
+    L: LfclkType,
+{
+    /// The source of the high frequency clock
+    pub fn hfclk_source(&self) -> HfclkSource {
+        H::SOURCE
+    }
+
+    /// The source of the low frequency clock
+    pub fn lfclk_source(&self) -> LfclkSource {
+        L::SOURCE
+    }
+
+    /// The accuracy of the low frequency clock in ppm
+    ///
+    /// Defaults to the 500 ppm required by BLE, which the LFRC meets when it
+    /// is calibrated regularly. Use `with_lfclk_accuracy` to specify the
+    /// tolerance of a crystal.
+    pub fn lfclk_accuracy_ppm(&self) -> u16 {
+        self.lfclk_accuracy_ppm
+    }
+
+    /// Specify the accuracy of the low frequency clock in ppm
+    pub fn with_lfclk_accuracy(self, ppm: u16) -> Self {
+        Frozen {
+            lfclk_accuracy_ppm: ppm,
+            _config: PhantomData,
+        }
+    }
+
+    /// Get a handle for requesting the external high frequency oscillator
+    pub fn hfxo_requester(&self) -> HfxoRequester {
+        HfxoRequester { _private: () }
+    }
+}
+
+#[cfg(not(feature = "9160"))]
+impl Frozen<ExternalOscillator, Internal, LfOscStarted> {
+    /// Handle the calibration events from the CLOCK interrupt
+    ///
+    /// See `Clocks::handle_calibration_events`. The calibration timer and the
+    /// interrupts need to be set up before freezing the configuration.
+    pub fn handle_calibration_events(&self) {
+        handle_calibration_events(clock());
+    }
+}
+
+/// The users of the external high frequency oscillator
+struct HfxoUsers {
+    /// Number of outstanding `HfxoRequest`s
+    requests: Cell<usize>,
+    /// Whether `Clocks` is in the `ExternalOscillator` state
+    clocks: Cell<bool>,
+}
+
+impl HfxoUsers {
+    fn set_clocks(&self, enabled: bool) {
+        self.clocks.set(enabled);
+    }
+
+    fn any(&self) -> bool {
+        self.requests.get() > 0 || self.clocks.get()
+    }
+}
+
+static HFXO_USERS: Mutex<HfxoUsers> = Mutex::new(HfxoUsers {
+    requests: Cell::new(0),
+    clocks: Cell::new(false),
+});
+
+/// Reference counted access to the external high frequency oscillator
+///
+/// Obtained from `Clocks::hfxo_requester` or `Frozen::hfxo_requester`. The
+/// oscillator is started by the first request and stopped once the last
+/// `HfxoRequest` is dropped, unless `Clocks::enable_ext_hfosc` has been used
+/// to keep it running.
+#[derive(Clone, Copy)]
+pub struct HfxoRequester {
+    _private: (),
+}
+
+impl HfxoRequester {
+    /// Request the external oscillator, blocking until it is running
+    ///
+    /// Sleeps until the oscillator has started. The CLOCK interrupt handler
+    /// may clear the `HfclkStarted` event, as this checks the clock's status.
+    pub fn request(&self) -> HfxoRequest {
+        let request = self.request_nowait();
+        wait_for_event(&clock(), INTERRUPT, 1 << 0, || request.is_running());
+        request
+    }
+
+    /// Request the external oscillator, without waiting for it to start
+    ///
+    /// Use `HfxoRequest::is_running` to find out when it has started.
+    pub fn request_nowait(&self) -> HfxoRequest {
+        interrupt::free(|cs| {
+            let users = HFXO_USERS.borrow(cs);
+            if !users.any() {
+                clock().tasks_hfclkstart.write(|w| unsafe { w.bits(1) });
+            }
+            users.requests.set(users.requests.get() + 1);
+        });
+
+        HfxoRequest { _private: () }
+    }
+}
+
+/// An outstanding request for the external high frequency oscillator
+///
+/// Releases the request when dropped.
+pub struct HfxoRequest {
+    _private: (),
+}
+
+impl HfxoRequest {
+    /// Whether the high frequency clock is running from the external
+    /// oscillator
+    pub fn is_running(&self) -> bool {
+        // SRC is set once the oscillator has started
+        let stat = clock().hfclkstat.read().bits();
+        stat & 1 != 0 && stat & (1 << 16) != 0
+    }
+}
+
+impl Drop for HfxoRequest {
+    fn drop(&mut self) {
+        interrupt::free(|cs| {
+            let users = HFXO_USERS.borrow(cs);
+            users.requests.set(users.requests.get() - 1);
+            if !users.any() {
+                clock().tasks_hfclkstop.write(|w| unsafe { w.bits(1) });
+            }
+        });
+    }
+}
+
+/// The CLOCK register block, for the reference counted HFXO requests and the
+/// LFRC calibration of a frozen configuration
+fn clock() -> &'static <CLOCK as core::ops::Deref>::Target {
+    // The HFXO requests only access the HFCLKSTART/STOP tasks and HFCLKSTAT,
+    // from within a critical section where the count is updated, so they
+    // don't interfere with `Clocks`. The calibration registers are only used
+    // through `Frozen` once `Clocks` is gone.
+    unsafe { &*CLOCK::ptr() }
+}
+
+/// Decode LFCLKSTAT.SRC
+#[cfg(not(feature = "9160"))]
+fn lfclk_source_from_bits(bits: u32) -> Option<LfclkSource> {
+    match bits {
+        0 => Some(LfclkSource::Internal),
+        1 => Some(LfclkSource::ExternalOscillator),
+        2 => Some(LfclkSource::Synthesized),
+        _ => None,
+    }
+}
+
+/// Decode LFCLKSTAT.SRC
+#[cfg(feature = "9160")]
+fn lfclk_source_from_bits(bits: u32) -> Option<LfclkSource> {
+    match bits {
+        1 => Some(LfclkSource::Internal),
+        2 => Some(LfclkSource::ExternalOscillator),
+        _ => None,
+    }
+}
+
+/// INTEN bit of an event
+fn event_mask(event: &ClocksEvent) -> u32 {
+    match *event {
+        ClocksEvent::HfclkStarted => 1 << 0,
+        ClocksEvent::LfclkStarted => 1 << 1,
+        #[cfg(not(feature = "9160"))]
+        ClocksEvent::Done => 1 << 3,
+        #[cfg(not(feature = "9160"))]
+        ClocksEvent::CalibrationTimeout => 1 << 4,
+    }
 }
 
 /// Allowable configuration options for the low frequency oscillator when
@@ -106,6 +559,49 @@ pub enum LfOscConfiguration {
     ExternalAndBypass,
 }
 
+impl<L, LSTAT> Clocks<HfxoStarting, L, LSTAT> {
+    /// Whether the high frequency clock is running from the external
+    /// oscillator
+    pub fn is_hfxo_running(&self) -> bool {
+        let stat = self.periph.hfclkstat.read().bits();
+        stat & 1 != 0 && stat & (1 << 16) != 0
+    }
+
+    /// Wait until the external oscillator is running
+    ///
+    /// The CLOCK interrupt handler may clear the `HfclkStarted` event, as
+    /// this checks the clock's status.
+    pub fn wait_hfxo(self) -> Clocks<ExternalOscillator, L, LSTAT> {
+        let clocks = &self;
+        wait_for_event(&self.periph, INTERRUPT, 1 << 0, || clocks.is_hfxo_running());
+
+        Clocks {
+            hfclk: ExternalOscillator,
+            lfclk: self.lfclk,
+            lfstat: self.lfstat,
+            periph: self.periph,
+        }
+    }
+}
+
+impl<H, L> Clocks<H, L, LfOscStarting> {
+    /// Wait until the low frequency clock is running
+    ///
+    /// The CLOCK interrupt handler may clear the `LfclkStarted` event, as
+    /// this checks the clock's status.
+    pub fn wait_lfclk(self) -> Clocks<H, L, LfOscStarted> {
+        let clocks = &self;
+        wait_for_event(&self.periph, INTERRUPT, 1 << 1, || clocks.lfclk_running());
+
+        Clocks {
+            hfclk: self.hfclk,
+            lfclk: self.lfclk,
+            lfstat: LfOscStarted,
+            periph: self.periph,
+        }
+    }
+}
+
 impl<H, L> Clocks<H, L, LfOscStarted> {
     /// Stop the Low Frequency clock
     pub fn stop_lfclk(self) -> Clocks<H, L, LfOscStopped> {
@@ -169,4 +665,101 @@ impl<H, L> Clocks<H, L, LfOscStopped> {
             periph: self.periph,
         }
     }
+
+    /// Use the internal RC Oscillator for the low frequency clock source
+    #[cfg(feature = "9160")]
+    pub fn set_lfclk_src_rc(self) -> Clocks<H, Internal, LfOscStopped> {
+        // LFCLKSRC.SRC = LFRC
+        self.periph.lfclksrc.write(|w| unsafe { w.bits(1) });
+        Clocks {
+            hfclk: self.hfclk,
+            lfclk: Internal,
+            lfstat: self.lfstat,
+            periph: self.periph,
+        }
+    }
+
+    /// Use the external 32.768 kHz crystal to drive the low frequency clock
+    ///
+    /// The nRF9160 doesn't support bypassing the crystal oscillator.
+    #[cfg(feature = "9160")]
+    pub fn set_lfclk_src_external(self) -> Clocks<H, ExternalOscillator, LfOscStopped> {
+        // LFCLKSRC.SRC = LFXO
+        self.periph.lfclksrc.write(|w| unsafe { w.bits(2) });
+        Clocks {
+            hfclk: self.hfclk,
+            lfclk: ExternalOscillator,
+            lfstat: self.lfstat,
+            periph: self.periph,
+        }
+    }
+}
+
+/// Calibration of the internal RC oscillator, which needs the external high
+/// frequency oscillator to be running
+///
+/// Calibrating the LFRC at least every 8 seconds, or when the temperature has
+/// changed by 0.5 °C, keeps it within the 500 ppm required for BLE.
+#[cfg(not(feature = "9160"))]
+impl Clocks<ExternalOscillator, Internal, LfOscStarted> {
+    /// Calibrate the LFRC, sleeping until calibration is done
+    ///
+    /// The CLOCK interrupt handler must not clear the `Done` event while this
+    /// sleeps.
+    pub fn calibrate_lfrc(&mut self) {
+        self.start_lfrc_calibration();
+        let periph = &self.periph;
+        wait_for_event(periph, INTERRUPT, 1 << 3, || periph.events_done.read().bits() != 0);
+        self.periph.events_done.reset();
+    }
+
+    /// Start calibrating the LFRC, without waiting for it to finish
+    ///
+    /// The `Done` event signals when calibration is done.
+    pub fn start_lfrc_calibration(&mut self) {
+        self.periph.events_done.reset();
+        self.periph.tasks_cal.write(|w| unsafe { w.bits(1) });
+    }
+
+    /// Start the calibration timer
+    ///
+    /// The timer generates the `CalibrationTimeout` event after `interval`
+    /// multiples of 0.25 seconds. `interval` must be between 1 and 127.
+    pub fn start_calibration_timer(&mut self, interval: u8) {
+        assert!((1..=127).contains(&interval));
+
+        self.periph.events_ctto.reset();
+        self.periph
+            .ctiv
+            .write(|w| unsafe { w.bits(u32::from(interval)) });
+        self.periph.tasks_ctstart.write(|w| unsafe { w.bits(1) });
+    }
+
+    /// Stop the calibration timer
+    pub fn stop_calibration_timer(&mut self) {
+        self.periph.tasks_ctstop.write(|w| unsafe { w.bits(1) });
+    }
+
+    /// Handle the calibration events from the CLOCK interrupt
+    ///
+    /// With the `Done` and `CalibrationTimeout` interrupts enabled and the
+    /// calibration timer started, calling this from the interrupt handler
+    /// calibrates the LFRC every timer interval: a timeout starts a
+    /// calibration, and a finished calibration restarts the timer.
+    pub fn handle_calibration_events(&mut self) {
+        handle_calibration_events(&self.periph);
+    }
+}
+
+#[cfg(not(feature = "9160"))]
+fn handle_calibration_events(periph: &<CLOCK as core::ops::Deref>::Target) {
+    if periph.events_ctto.read().bits() != 0 {
+        periph.events_ctto.reset();
+        periph.events_done.reset();
+        periph.tasks_cal.write(|w| unsafe { w.bits(1) });
+    }
+    if periph.events_done.read().bits() != 0 {
+        periph.events_done.reset();
+        periph.tasks_ctstart.write(|w| unsafe { w.bits(1) });
+    }
 }
@@ -7,6 +7,10 @@ use crate::hal::blocking::delay::{DelayMs, DelayUs};
 use crate::clocks::HFCLK_FREQ;
 
 /// System timer (SysTick) as a delay provider
+///
+/// Assumes the CPU runs at `HFCLK_FREQ`. To leave SysTick to an RTOS or RTFM,
+/// use a `Timer`, which also implements `DelayMs` and `DelayUs`, or an `Rtc`
+/// for long delays in a low power mode.
 pub struct Delay {
     syst: SYST,
 }
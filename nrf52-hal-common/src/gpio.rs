@@ -66,6 +66,20 @@ use crate::hal::digital::v2::{OutputPin, StatefulOutputPin, InputPin};
 use void::Void;
 
 impl<MODE> Pin<MODE> {
+    /// Recreate a pin that was consumed when it was connected to a
+    /// peripheral's PSEL register, once it has been disconnected again
+    ///
+    /// Unsafe, as the caller must make sure that no other `Pin` for the same
+    /// pin exists.
+    #[cfg(feature = "52840")]
+    pub(crate) unsafe fn from_psel(pin: u8, port: bool) -> Self {
+        Pin {
+            _mode: PhantomData,
+            port,
+            pin,
+        }
+    }
+
     /// Convert the pin to be a floating input
     pub fn into_floating_input(self) -> Pin<Input<Floating>> {
         unsafe {
@@ -220,6 +234,37 @@ impl<MODE> Pin<MODE> {
     }
 }
 
+/// Level detected by the SENSE mechanism of an input pin
+///
+/// A sensing pin can wake the system from System OFF.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Sense {
+    Disabled,
+    High,
+    Low,
+}
+
+impl<MODE> Pin<Input<MODE>> {
+    /// Configure the SENSE mechanism of the pin
+    pub fn set_sense(&mut self, sense: Sense) {
+        // This is safe, as we restrict our access to the dedicated
+        // register for this pin.
+        let pin_cnf = unsafe {
+            &(*{
+                #[cfg(not(feature = "52840"))]
+                { P0::ptr() }
+                #[cfg(feature = "52840")]
+                { if !self.port { P0::ptr() } else { P1::ptr() } }
+            }).pin_cnf[self.pin as usize]
+        };
+        pin_cnf.modify(|_, w| match sense {
+            Sense::Disabled => w.sense().disabled(),
+            Sense::High => w.sense().high(),
+            Sense::Low => w.sense().low(),
+        });
+    }
+}
+
 impl<MODE> InputPin for Pin<Input<MODE>> {
     type Error = Void;
 
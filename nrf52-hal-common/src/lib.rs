@@ -14,20 +14,36 @@ pub use nrf52840_pac as target;
 #[cfg(feature = "9160")]
 pub use nrf9160_pac as target;
 
+// Declared first, as it provides the `impl_async_instance!` macro to the
+// driver modules
+#[cfg(feature = "async")]
+#[macro_use]
+pub mod waker;
+
 pub mod clocks;
 pub mod delay;
 pub mod gpio;
+// The nRF9160 splits System OFF, the DC/DC converter and RAM retention
+// across its POWER, REGULATORS and VMC peripherals, which aren't covered
+#[cfg(not(feature="9160"))]
+pub mod power;
 #[cfg(not(feature="9160"))]
 pub mod rng;
+#[cfg(feature="9160")]
+#[path = "rng_cryptocell.rs"]
+pub mod rng;
 pub mod rtc;
 pub mod saadc;
 pub mod spim;
+// The nRF9160 has no TEMP peripheral the application can access, its die
+// temperature is only available from the modem
 #[cfg(not(feature="9160"))]
 pub mod temp;
 pub mod time;
 pub mod timer;
 pub mod twim;
 pub mod uarte;
+mod wait;
 
 pub mod prelude {
     pub use crate::hal::prelude::*;
@@ -63,6 +79,13 @@ pub(crate) fn slice_in_ram(slice: &[u8]) -> bool {
         (ptr + slice.len()) < target_constants::SRAM_UPPER
 }
 
+/// Does this list of arrays reside entirely within RAM?
+pub(crate) fn list_in_ram<const N: usize>(list: &[[u8; N]]) -> bool {
+    let ptr = list.as_ptr() as usize;
+    ptr >= target_constants::SRAM_LOWER &&
+        (ptr + list.len() * N) < target_constants::SRAM_UPPER
+}
+
 /// A handy structure for converting rust slices into ptr and len pairs
 /// for use with EasyDMA. Care must be taken to make sure mutability
 /// guarantees are respected
@@ -85,11 +108,20 @@ impl DmaSlice {
             len: slice.len() as u32,
         }
     }
+
+    /// Describe the first array of `list`, for use with the EasyDMA
+    /// ArrayList mode. In this mode the peripheral advances the pointer by
+    /// `N` bytes after every transfer.
+    pub fn from_array_list<const N: usize>(list: &[[u8; N]]) -> Self {
+        Self {
+            ptr: list.as_ptr() as u32,
+            len: N as u32,
+        }
+    }
 }
 
 pub use crate::clocks::Clocks;
 pub use crate::delay::Delay;
-#[cfg(not(feature="9160"))]
 pub use crate::rng::Rng;
 pub use crate::rtc::Rtc;
 pub use crate::saadc::Saadc;
@@ -0,0 +1,367 @@
+//! HAL interface to the POWER peripheral
+//!
+//! See product specification:
+//!
+//! - nrf52832: Section 18
+//! - nrf52840: Section 5.3
+//!
+//! Not available on the nRF9160, which splits these functions across its
+//! POWER, REGULATORS and VMC peripherals.
+
+use cortex_m::asm;
+
+use crate::target::{Interrupt, NVIC, POWER};
+
+/// Reason for the most recent reset
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ResetReason {
+    /// Power-on or brownout reset, which leaves RESETREAS empty
+    PowerOn,
+    /// The reset pin
+    ResetPin,
+    /// The watchdog
+    Watchdog,
+    /// A soft reset, e.g. through `SCB::sys_reset`
+    SoftReset,
+    /// A CPU lock-up
+    Lockup,
+    /// Wakeup from System OFF by a GPIO pin's SENSE mechanism
+    SystemOffGpio,
+    /// Wakeup from System OFF by LPCOMP
+    SystemOffLpcomp,
+    /// Wakeup from System OFF mode when entering debug interface mode
+    SystemOffDebug,
+    /// Wakeup from System OFF by an NFC field
+    #[cfg(any(feature = "52832", feature = "52840"))]
+    SystemOffNfc,
+    /// Wakeup from System OFF by VBUS rising into the valid range
+    #[cfg(feature = "52840")]
+    SystemOffVbus,
+}
+
+/// Threshold of the power-fail comparator, in tenths of a volt
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PofThreshold {
+    V17,
+    V18,
+    V19,
+    V20,
+    V21,
+    V22,
+    V23,
+    V24,
+    V25,
+    V26,
+    V27,
+    V28,
+}
+
+/// Threshold of the power-fail comparator for VDDH, in tenths of a volt
+#[cfg(feature = "52840")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VddhPofThreshold {
+    V27,
+    V28,
+    V29,
+    V30,
+    V31,
+    V32,
+    V33,
+    V34,
+    V35,
+    V36,
+    V37,
+    V38,
+    V39,
+    V40,
+    V41,
+    V42,
+}
+
+/// Events generated by the POWER peripheral
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PowerEvent {
+    /// The supply voltage dropped below the power-fail threshold
+    PowerFailWarning,
+    SleepEnter,
+    SleepExit,
+    /// A voltage supply on VBUS was detected
+    #[cfg(feature = "52840")]
+    UsbDetected,
+    /// The voltage supply on VBUS was removed
+    #[cfg(feature = "52840")]
+    UsbRemoved,
+    /// The USB regulator output has settled
+    #[cfg(feature = "52840")]
+    UsbPowerReady,
+}
+
+/// Interface to the POWER peripheral
+pub struct Power(POWER);
+
+impl Power {
+    pub fn new(power: POWER) -> Self {
+        Power(power)
+    }
+
+    /// Return the raw interface to the underlying POWER peripheral
+    pub fn free(self) -> POWER {
+        self.0
+    }
+
+    /// Read the reason for the most recent reset
+    ///
+    /// RESETREAS accumulates the reasons of all resets since it was last
+    /// cleared. If several are recorded, the first one in register order is
+    /// returned, so clear the register after reading it with
+    /// `clear_reset_reason`.
+    pub fn reset_reason(&self) -> ResetReason {
+        let reasons = self.0.resetreas.read().bits();
+
+        let decoded = [
+            (0, ResetReason::ResetPin),
+            (1, ResetReason::Watchdog),
+            (2, ResetReason::SoftReset),
+            (3, ResetReason::Lockup),
+            (16, ResetReason::SystemOffGpio),
+            (17, ResetReason::SystemOffLpcomp),
+            (18, ResetReason::SystemOffDebug),
+            #[cfg(any(feature = "52832", feature = "52840"))]
+            (19, ResetReason::SystemOffNfc),
+            #[cfg(feature = "52840")]
+            (20, ResetReason::SystemOffVbus),
+        ];
+
+        decoded
+            .iter()
+            .find(|(bit, _)| reasons & (1 << bit) != 0)
+            .map(|&(_, reason)| reason)
+            .unwrap_or(ResetReason::PowerOn)
+    }
+
+    /// Clear the recorded reset reasons
+    pub fn clear_reset_reason(&mut self) {
+        // The register's fields are cleared by writing 1 to them
+        self.0.resetreas.write(|w| unsafe { w.bits(0xFFFF_FFFF) });
+    }
+
+    /// Enter System OFF, the deepest power saving mode
+    ///
+    /// The system can only be woken by a reset, or by the configured wakeup
+    /// sources: input pins with a SENSE level (see `Pin::set_sense`), LPCOMP,
+    /// NFC field detection and, on the nRF52840, VBUS. Waking up resets the
+    /// system, so this never returns. RAM is only retained as configured
+    /// with `set_ram_retention`.
+    pub fn system_off(&mut self) -> ! {
+        self.0.systemoff.write(|w| unsafe { w.bits(1) });
+
+        // In debug interface mode, System OFF is emulated and the CPU keeps
+        // running
+        loop {
+            asm::wfe();
+        }
+    }
+
+    /// Configure which sections of a RAM block are retained in System OFF
+    ///
+    /// Bit `n` of `sections` selects section `n` of RAM block `block`.
+    /// Sections that aren't selected are not affected.
+    ///
+    /// The nRF52810 has blocks 0 to 2 and the nRF52832 blocks 0 to 7, each
+    /// with sections 0 and 1. The nRF52840 has blocks 0 to 7 with sections 0
+    /// and 1, and block 8 with sections 0 to 5. Panics if `block` or one of
+    /// the selected sections doesn't exist.
+    pub fn set_ram_retention(&mut self, block: usize, sections: u16, retain: bool) {
+        assert!(block < RAM_SECTIONS.len());
+        assert!(u32::from(sections) >> RAM_SECTIONS[block] == 0);
+
+        let mask = u32::from(sections) << 16;
+
+        if retain {
+            self.0.ram[block].powerset.write(|w| unsafe { w.bits(mask) });
+        } else {
+            self.0.ram[block].powerclr.write(|w| unsafe { w.bits(mask) });
+        }
+    }
+
+    /// Enable the power-fail comparator, or disable it with `None`
+    ///
+    /// While enabled, the `PowerFailWarning` event is generated when the
+    /// supply voltage drops below the threshold.
+    pub fn set_pof_threshold(&mut self, threshold: Option<PofThreshold>) {
+        match threshold {
+            None => self.0.pofcon.modify(|r, w| unsafe { w.bits(r.bits() & !1) }),
+            Some(threshold) => {
+                // V17 is 4, the values below that are reserved
+                let value = threshold as u32 + 4;
+                self.0.pofcon.modify(|r, w| unsafe {
+                    w.bits((r.bits() & !0b1_1111) | value << 1 | 1)
+                });
+            }
+        }
+    }
+
+    /// Set the threshold of the power-fail comparator for VDDH
+    ///
+    /// This threshold applies in high voltage mode, in addition to the one
+    /// for VDD. The comparator is enabled through `set_pof_threshold`.
+    #[cfg(feature = "52840")]
+    pub fn set_vddh_pof_threshold(&mut self, threshold: VddhPofThreshold) {
+        let value = threshold as u32;
+        self.0.pofcon.modify(|r, w| unsafe {
+            w.bits((r.bits() & !(0b1111 << 8)) | value << 8)
+        });
+    }
+
+    /// Select the constant latency or the low power sub-mode of System ON
+    ///
+    /// In the default low power sub-mode, the regulators and oscillators the
+    /// system needs are turned off while the CPU sleeps in WFE or WFI, which
+    /// adds to the time it takes to wake up. The constant latency sub-mode
+    /// keeps them running, so wakeup latency stays minimal at the cost of a
+    /// higher idle current.
+    pub fn set_constant_latency(&mut self, enabled: bool) {
+        if enabled {
+            self.0.tasks_constlat.write(|w| unsafe { w.bits(1) });
+        } else {
+            self.0.tasks_lowpwr.write(|w| unsafe { w.bits(1) });
+        }
+    }
+
+    /// Enable or disable the DC/DC converter of REG1
+    ///
+    /// The converter needs the external inductor to be present.
+    pub fn set_dcdc(&mut self, enabled: bool) {
+        self.0.dcdcen.write(|w| w.dcdcen().bit(enabled));
+    }
+
+    /// Enable or disable the DC/DC converter of REG0, the regulator used in
+    /// high voltage mode
+    #[cfg(feature = "52840")]
+    pub fn set_dcdc_reg0(&mut self, enabled: bool) {
+        self.0.dcdcen0.write(|w| w.dcdcen().bit(enabled));
+    }
+
+    /// Whether the system is supplied through VDDH, in high voltage mode
+    #[cfg(feature = "52840")]
+    pub fn is_high_voltage_mode(&self) -> bool {
+        self.0.mainregstatus.read().bits() & 1 != 0
+    }
+
+    /// Whether a voltage supply on VBUS is detected
+    #[cfg(feature = "52840")]
+    pub fn is_usb_detected(&self) -> bool {
+        self.0.usbregstatus.read().bits() & 1 != 0
+    }
+
+    /// Whether the USB regulator output is ready
+    #[cfg(feature = "52840")]
+    pub fn is_usb_power_ready(&self) -> bool {
+        self.0.usbregstatus.read().bits() & (1 << 1) != 0
+    }
+
+    /// Read the general purpose retention register GPREGRET
+    ///
+    /// Its contents are retained through System OFF and soft resets.
+    pub fn gpregret(&self) -> u8 {
+        self.0.gpregret.read().bits() as u8
+    }
+
+    /// Write the general purpose retention register GPREGRET
+    pub fn set_gpregret(&mut self, value: u8) {
+        self.0.gpregret.write(|w| unsafe { w.bits(u32::from(value)) });
+    }
+
+    /// Read the general purpose retention register GPREGRET2
+    pub fn gpregret2(&self) -> u8 {
+        self.0.gpregret2.read().bits() as u8
+    }
+
+    /// Write the general purpose retention register GPREGRET2
+    pub fn set_gpregret2(&mut self, value: u8) {
+        self.0.gpregret2.write(|w| unsafe { w.bits(u32::from(value)) });
+    }
+
+    /// Enable the generation of a hardware interrupt from a given event
+    ///
+    /// If access to the NVIC is not provided, the interrupt must ALSO be enabled
+    /// there outside of this function (e.g. manually call `nvic.enable`, or through
+    /// the use of RTFM).
+    pub fn enable_interrupt(&mut self, event: PowerEvent, nvic: Option<&mut NVIC>) {
+        self.0
+            .intenset
+            .write(|w| unsafe { w.bits(event_mask(&event)) });
+
+        if let Some(_nvic) = nvic {
+            unsafe { NVIC::unmask(Interrupt::POWER_CLOCK) };
+        }
+    }
+
+    /// Disable the generation of a hardware interrupt from a given event
+    ///
+    /// If access to the NVIC is not provided, the interrupt must ALSO be disabled
+    /// there outside of this function (e.g. manually call `nvic.disable`, or through
+    /// the use of RTFM).
+    pub fn disable_interrupt(&mut self, event: PowerEvent, nvic: Option<&mut NVIC>) {
+        self.0
+            .intenclr
+            .write(|w| unsafe { w.bits(event_mask(&event)) });
+
+        if let Some(_nvic) = nvic {
+            NVIC::mask(Interrupt::POWER_CLOCK);
+        }
+    }
+
+    /// Whether the given event has occurred
+    pub fn is_event_triggered(&self, event: PowerEvent) -> bool {
+        match event {
+            PowerEvent::PowerFailWarning => self.0.events_pofwarn.read().bits() != 0,
+            PowerEvent::SleepEnter => self.0.events_sleepenter.read().bits() != 0,
+            PowerEvent::SleepExit => self.0.events_sleepexit.read().bits() != 0,
+            #[cfg(feature = "52840")]
+            PowerEvent::UsbDetected => self.0.events_usbdetected.read().bits() != 0,
+            #[cfg(feature = "52840")]
+            PowerEvent::UsbRemoved => self.0.events_usbremoved.read().bits() != 0,
+            #[cfg(feature = "52840")]
+            PowerEvent::UsbPowerReady => self.0.events_usbpwrrdy.read().bits() != 0,
+        }
+    }
+
+    /// Clear the given event
+    pub fn reset_event(&mut self, event: PowerEvent) {
+        match event {
+            PowerEvent::PowerFailWarning => self.0.events_pofwarn.reset(),
+            PowerEvent::SleepEnter => self.0.events_sleepenter.reset(),
+            PowerEvent::SleepExit => self.0.events_sleepexit.reset(),
+            #[cfg(feature = "52840")]
+            PowerEvent::UsbDetected => self.0.events_usbdetected.reset(),
+            #[cfg(feature = "52840")]
+            PowerEvent::UsbRemoved => self.0.events_usbremoved.reset(),
+            #[cfg(feature = "52840")]
+            PowerEvent::UsbPowerReady => self.0.events_usbpwrrdy.reset(),
+        }
+    }
+}
+
+/// Number of sections of each RAM block
+#[cfg(feature = "52810")]
+const RAM_SECTIONS: &[usize] = &[2, 2, 2];
+#[cfg(feature = "52832")]
+const RAM_SECTIONS: &[usize] = &[2, 2, 2, 2, 2, 2, 2, 2];
+#[cfg(feature = "52840")]
+const RAM_SECTIONS: &[usize] = &[2, 2, 2, 2, 2, 2, 2, 2, 6];
+
+/// INTEN bit of an event
+fn event_mask(event: &PowerEvent) -> u32 {
+    match *event {
+        PowerEvent::PowerFailWarning => 1 << 2,
+        PowerEvent::SleepEnter => 1 << 5,
+        PowerEvent::SleepExit => 1 << 6,
+        #[cfg(feature = "52840")]
+        PowerEvent::UsbDetected => 1 << 7,
+        #[cfg(feature = "52840")]
+        PowerEvent::UsbRemoved => 1 << 8,
+        #[cfg(feature = "52840")]
+        PowerEvent::UsbPowerReady => 1 << 9,
+    }
+}
@@ -1,39 +1,175 @@
 //! HAL interface to the RNG peripheral
 //!
 //! See nRF52832 product specification, chapter 26.
+//!
+//! Besides generating bytes on demand, the RNG can fill a pool of
+//! `POOL_SIZE` bytes in the background. Call `Rng::start_background` and
+//! `on_interrupt` from the RNG interrupt handler:
+//!
+//! ``` ignore
+//! #[interrupt]
+//! fn RNG() {
+//!     rng::on_interrupt();
+//! }
+//! ```
+
+
+use core::cell::RefCell;
+
+use cortex_m::interrupt::{self, Mutex};
+use rand_core::{CryptoRng, ErrorKind, RngCore};
 
+use crate::target::{Interrupt, NVIC, RNG};
+use crate::wait::wait_for_event;
 
-use rand_core::{CryptoRng, RngCore};
 
-use crate::target::RNG;
+/// Number of bytes the background pool holds
+pub const POOL_SIZE: usize = 64;
+
+/// Random bytes generated in the background, ready to be consumed
+static POOL: Mutex<RefCell<Pool>> = Mutex::new(RefCell::new(Pool::new()));
 
 
 /// Interface to the RNG peripheral
-///
-/// Right now, this is very basic, only providing blocking interfaces.
-pub struct Rng(RNG);
+pub struct Rng {
+    rng: RNG,
+    background: bool,
+}
 
 impl Rng {
+    /// Create the driver, with bias correction enabled
     pub fn new(rng: RNG) -> Self {
         rng.config.write(|w| w.dercen().enabled());
-        Self(rng)
+        Self {
+            rng,
+            background: false,
+        }
+    }
+
+    /// Enable or disable the bias correction
+    ///
+    /// The bias correction makes the bits uniformly distributed, but
+    /// generating a byte takes about four times as long without it.
+    pub fn set_bias_correction(&mut self, enabled: bool) {
+        self.rng.config.write(|w| w.dercen().bit(enabled));
+    }
+
+    /// Return the raw interface to the underlying RNG peripheral
+    ///
+    /// Stops the background generation, but doesn't disable the interrupt in
+    /// the NVIC.
+    pub fn free(mut self) -> RNG {
+        self.stop_background(None);
+        self.rng
+    }
+
+    /// Start filling the pool in the background
+    ///
+    /// Each new byte generates an interrupt, from which `on_interrupt` must
+    /// be called. The generation is paused while the pool is full, and
+    /// resumes when bytes are taken from it.
+    ///
+    /// If access to the NVIC is not provided, the interrupt must ALSO be enabled
+    /// there outside of this function (e.g. manually call `nvic.enable`, or through
+    /// the use of RTFM).
+    pub fn start_background(&mut self, nvic: Option<&mut NVIC>) {
+        self.background = true;
+        self.rng.intenset.write(|w| w.valrdy().set());
+
+        if let Some(_nvic) = nvic {
+            unsafe { NVIC::unmask(Interrupt::RNG) };
+        }
+
+        self.rng.tasks_start.write(|w| unsafe { w.bits(1) });
+    }
+
+    /// Stop filling the pool in the background
+    ///
+    /// Discards the bytes that remain in the pool.
+    ///
+    /// If access to the NVIC is not provided, the interrupt must ALSO be disabled
+    /// there outside of this function (e.g. manually call `nvic.disable`, or through
+    /// the use of RTFM).
+    pub fn stop_background(&mut self, nvic: Option<&mut NVIC>) {
+        self.background = false;
+        self.rng.tasks_stop.write(|w| unsafe { w.bits(1) });
+        self.rng.intenclr.write(|w| w.valrdy().clear());
+        self.rng.events_valrdy.write(|w| unsafe { w.bits(0) });
+
+        interrupt::free(|cs| POOL.borrow(cs).borrow_mut().clear());
+
+        if let Some(_nvic) = nvic {
+            NVIC::mask(Interrupt::RNG);
+        }
+    }
+
+    /// Number of bytes in the background pool
+    pub fn available(&self) -> usize {
+        interrupt::free(|cs| POOL.borrow(cs).borrow().len)
     }
 
     /// Fill the provided buffer with random bytes
     ///
-    /// Will block until the buffer is full.
+    /// Will block until the buffer is full. With the background generation
+    /// running, the bytes are taken from the pool, waiting for it to refill
+    /// as needed. New bytes that the interrupt handler hasn't moved into the
+    /// pool are taken directly, so this also works where the handler can't
+    /// run, e.g. from an interrupt of the same or a higher priority.
+    ///
+    /// Without the background generation, the RNG interrupt handler must not
+    /// clear the VALRDY event while this blocks, or it never returns.
     pub fn random(&mut self, buf: &mut [u8]) {
-        self.0.tasks_start.write(|w| unsafe { w.bits(1) });
+        if self.background {
+            let rng = &self.rng;
+            let mut filled = 0;
+            wait_for_event(rng, Interrupt::RNG, 1 << 0, || {
+                filled += self.take(&mut buf[filled..]);
+
+                if filled < buf.len() {
+                    interrupt::free(|_| {
+                        if rng.events_valrdy.read().bits() != 0 {
+                            rng.events_valrdy.write(|w| unsafe { w.bits(0) });
+                            buf[filled] = rng.value.read().value().bits();
+                            filled += 1;
+
+                            // The byte has been handled
+                            NVIC::unpend(Interrupt::RNG);
+                        }
+                    });
+                }
+
+                filled == buf.len()
+            });
+            return;
+        }
+
+        self.rng.tasks_start.write(|w| unsafe { w.bits(1) });
 
+        let rng = &self.rng;
         for b in buf {
             // Wait for random byte to become ready, reset the flag once it is
-            while self.0.events_valrdy.read().bits() == 0 {}
-            self.0.events_valrdy.write(|w| unsafe { w.bits(0) });
+            wait_for_event(rng, Interrupt::RNG, 1 << 0, || {
+                rng.events_valrdy.read().bits() != 0
+            });
+            rng.events_valrdy.write(|w| unsafe { w.bits(0) });
 
-            *b = self.0.value.read().value().bits();
+            *b = rng.value.read().value().bits();
         }
 
-        self.0.tasks_stop.write(|w| unsafe { w.bits(1) });
+        self.rng.tasks_stop.write(|w| unsafe { w.bits(1) });
+    }
+
+    /// Move as many bytes as available from the pool into `buf`
+    ///
+    /// Returns the number of bytes moved, and resumes the generation if any
+    /// were.
+    fn take(&self, buf: &mut [u8]) -> usize {
+        let taken = interrupt::free(|cs| POOL.borrow(cs).borrow_mut().take(buf));
+
+        if taken > 0 {
+            self.rng.tasks_start.write(|w| unsafe { w.bits(1) });
+        }
+        taken
     }
 
     /// Return a random `u8`
@@ -89,9 +225,110 @@ impl RngCore for Rng {
         self.random(dest)
     }
 
+    /// Fill `dest` without blocking, if the background generation is running
+    ///
+    /// Takes the bytes from the pool, or returns an error and leaves the pool
+    /// untouched if it holds fewer than `dest.len()` bytes. Requests larger
+    /// than `POOL_SIZE` always fail. Without the background generation, this
+    /// blocks like `fill_bytes`.
     fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
-        Ok(self.fill_bytes(dest))
+        if !self.background {
+            self.fill_bytes(dest);
+            return Ok(());
+        }
+
+        if dest.len() > POOL_SIZE {
+            return Err(rand_core::Error::new(
+                ErrorKind::Unavailable,
+                "request exceeds the RNG pool size",
+            ));
+        }
+
+        let enough = interrupt::free(|cs| POOL.borrow(cs).borrow().len >= dest.len());
+        if !enough {
+            return Err(rand_core::Error::new(
+                ErrorKind::NotReady,
+                "not enough random bytes in the RNG pool",
+            ));
+        }
+
+        // The pool only shrinks through `Rng`, which is borrowed mutably
+        self.take(dest);
+        Ok(())
     }
 }
 
 impl CryptoRng for Rng {}
+
+/// Move a new byte into the pool
+///
+/// Call this from the RNG interrupt handler while the background generation
+/// is running.
+pub fn on_interrupt() {
+    // The register block is only used to handle the VALRDY event, which
+    // doesn't interfere with the `Rng` owning the instance.
+    let rng = unsafe { &*RNG::ptr() };
+
+    if rng.events_valrdy.read().bits() == 0 {
+        return;
+    }
+    rng.events_valrdy.write(|w| unsafe { w.bits(0) });
+    let value = rng.value.read().value().bits();
+
+    let full = interrupt::free(|cs| {
+        let mut pool = POOL.borrow(cs).borrow_mut();
+        pool.push(value);
+        pool.len == POOL_SIZE
+    });
+
+    if full {
+        rng.tasks_stop.write(|w| unsafe { w.bits(1) });
+    }
+}
+
+/// Ring buffer of random bytes
+struct Pool {
+    buf: [u8; POOL_SIZE],
+    start: usize,
+    len: usize,
+}
+
+impl Pool {
+    const fn new() -> Self {
+        Pool {
+            buf: [0; POOL_SIZE],
+            start: 0,
+            len: 0,
+        }
+    }
+
+    /// Append a byte, dropping it if the pool is full
+    fn push(&mut self, byte: u8) {
+        if self.len < POOL_SIZE {
+            self.buf[(self.start + self.len) % POOL_SIZE] = byte;
+            self.len += 1;
+        }
+    }
+
+    /// Discard all bytes
+    fn clear(&mut self) {
+        self.buf = [0; POOL_SIZE];
+        self.start = 0;
+        self.len = 0;
+    }
+
+    /// Move up to `dest.len()` bytes out of the pool
+    fn take(&mut self, dest: &mut [u8]) -> usize {
+        let count = dest.len().min(self.len);
+
+        for b in &mut dest[..count] {
+            *b = self.buf[self.start];
+            // Don't leave used random bytes behind in memory
+            self.buf[self.start] = 0;
+            self.start = (self.start + 1) % POOL_SIZE;
+        }
+        self.len -= count;
+
+        count
+    }
+}
@@ -0,0 +1,246 @@
+//! HAL interface to the TRNG of the nRF9160's CryptoCell
+//!
+//! The nRF9160 has no RNG peripheral. Its random numbers come from the true
+//! random number generator of the Arm CryptoCell 310, which is only
+//! accessible from the secure state. See the CRYPTOCELL chapter of the
+//! nRF9160 product specification.
+//!
+//! The TRNG collects 192 bits at a time. Blocks that fail the hardware's
+//! statistical tests are discarded.
+
+use rand_core::{CryptoRng, ErrorKind, RngCore};
+
+use crate::target::CRYPTOCELL_S;
+
+
+/// Offset of the CryptoCell's own registers from the peripheral's base address
+const CC_REGS_OFFSET: usize = 0x1000;
+
+// TRNG registers, relative to the CryptoCell's registers
+const RNG_IMR: usize = 0x100;
+const RNG_ISR: usize = 0x104;
+const RNG_ICR: usize = 0x108;
+const TRNG_CONFIG: usize = 0x10C;
+const EHR_DATA: usize = 0x114;
+const RND_SOURCE_ENABLE: usize = 0x12C;
+const SAMPLE_CNT1: usize = 0x130;
+const TRNG_DEBUG_CONTROL: usize = 0x138;
+const RNG_SW_RESET: usize = 0x140;
+const RNG_CLK_ENABLE: usize = 0x1C4;
+
+/// RNG_ISR: a block of random bits is ready in EHR_DATA
+const ISR_EHR_VALID: u32 = 1 << 0;
+/// RNG_ISR: the autocorrelation, CRNGT or von Neumann test failed
+const ISR_ERRORS: u32 = 0b1110;
+
+/// Number of EHR_DATA registers, making up one 192 bit block
+const EHR_WORDS: usize = 6;
+
+/// Number of ring oscillator cycles between two samples
+const SAMPLE_COUNT: u32 = 1000;
+
+/// Number of attempts to configure the TRNG after its software reset
+const MAX_RESET_POLLS: u32 = 1000;
+
+/// Number of times RNG_ISR is polled for a block, a few hundred milliseconds
+/// at 64 MHz
+const MAX_BLOCK_POLLS: u32 = 10_000_000;
+
+/// Number of consecutive blocks that may fail the statistical tests before
+/// the TRNG is considered broken
+const MAX_FAILED_BLOCKS: u32 = 8;
+
+
+/// Interface to the CryptoCell's TRNG
+///
+/// Implements `RngCore` and `CryptoRng`, and provides the `random*` methods
+/// of the nRF52 RNG driver. The TRNG always applies its von Neumann
+/// corrector, and there is no background generation, so `set_bias_correction`,
+/// `start_background` and `available` have no counterpart here.
+pub struct Rng(CRYPTOCELL_S);
+
+impl Rng {
+    /// Create the interface
+    ///
+    /// The CryptoCell is only enabled while random bits are collected.
+    pub fn new(cryptocell: CRYPTOCELL_S) -> Self {
+        Self(cryptocell)
+    }
+
+    /// Return the raw interface to the underlying CRYPTOCELL peripheral
+    pub fn free(self) -> CRYPTOCELL_S {
+        self.0
+    }
+
+    /// Fill the provided buffer with random bytes
+    ///
+    /// Will block until the buffer is full. Panics if the TRNG doesn't
+    /// deliver, use `RngCore::try_fill_bytes` to handle that case.
+    pub fn random(&mut self, buf: &mut [u8]) {
+        self.try_random(buf).expect("CryptoCell TRNG failed");
+    }
+
+    /// Fill the provided buffer with random bytes, or fail if the TRNG
+    /// doesn't come out of reset, or its blocks keep failing the tests or
+    /// don't arrive in time
+    fn try_random(&mut self, buf: &mut [u8]) -> Result<(), rand_core::Error> {
+        let result = self.start().and_then(|()| {
+            buf.chunks_mut(EHR_WORDS * 4).try_for_each(|dest| {
+                let block = self.next_block()?;
+                dest.copy_from_slice(&block[..dest.len()]);
+                Ok(())
+            })
+        });
+
+        self.stop();
+        result
+    }
+
+    /// Wait for the next block of random bits that passes the tests
+    fn next_block(&mut self) -> Result<[u8; EHR_WORDS * 4], rand_core::Error> {
+        let mut failed = 0;
+
+        for _ in 0..MAX_BLOCK_POLLS {
+            let status = self.read(RNG_ISR);
+
+            if status & ISR_ERRORS != 0 {
+                failed += 1;
+                if failed == MAX_FAILED_BLOCKS {
+                    return Err(rand_core::Error::new(
+                        ErrorKind::Unavailable,
+                        "TRNG blocks keep failing the statistical tests",
+                    ));
+                }
+
+                // The TRNG stops on errors, so discard the block and restart
+                self.write(RNG_ICR, 0xFFFF_FFFF);
+                self.write(RND_SOURCE_ENABLE, 0);
+                self.write(RND_SOURCE_ENABLE, 1);
+            } else if status & ISR_EHR_VALID != 0 {
+                let mut block = [0; EHR_WORDS * 4];
+                for (i, word) in block.chunks_mut(4).enumerate() {
+                    word.copy_from_slice(&self.read(EHR_DATA + i * 4).to_le_bytes());
+                }
+
+                // Reading the block lets the TRNG collect the next one
+                self.write(RNG_ICR, 0xFFFF_FFFF);
+                return Ok(block);
+            }
+        }
+
+        Err(rand_core::Error::new(
+            ErrorKind::Unavailable,
+            "timed out waiting for a TRNG block",
+        ))
+    }
+
+    /// Enable the CryptoCell and start collecting random bits
+    fn start(&mut self) -> Result<(), rand_core::Error> {
+        self.0.enable.write(|w| unsafe { w.bits(1) });
+
+        self.write(RNG_CLK_ENABLE, 1);
+        self.write(RNG_SW_RESET, 1);
+
+        // The reset takes a few cycles, during which writes are ignored
+        let mut polls = 0;
+        loop {
+            self.write(RNG_CLK_ENABLE, 1);
+            self.write(SAMPLE_CNT1, SAMPLE_COUNT);
+            if self.read(SAMPLE_CNT1) == SAMPLE_COUNT {
+                break;
+            }
+
+            polls += 1;
+            if polls == MAX_RESET_POLLS {
+                return Err(rand_core::Error::new(
+                    ErrorKind::Unavailable,
+                    "TRNG didn't come out of reset",
+                ));
+            }
+        }
+
+        // Shortest ring oscillator, all statistical tests enabled, status
+        // polled through RNG_ISR
+        self.write(TRNG_CONFIG, 0);
+        self.write(TRNG_DEBUG_CONTROL, 0);
+        self.write(RNG_IMR, 0xFFFF_FFFF);
+        self.write(RNG_ICR, 0xFFFF_FFFF);
+
+        self.write(RND_SOURCE_ENABLE, 1);
+
+        Ok(())
+    }
+
+    /// Stop collecting random bits and disable the CryptoCell
+    fn stop(&mut self) {
+        self.write(RND_SOURCE_ENABLE, 0);
+        self.write(RNG_CLK_ENABLE, 0);
+
+        self.0.enable.write(|w| unsafe { w.bits(0) });
+    }
+
+    fn read(&self, offset: usize) -> u32 {
+        unsafe { (register(offset) as *const u32).read_volatile() }
+    }
+
+    fn write(&mut self, offset: usize, value: u32) {
+        unsafe { register(offset).write_volatile(value) }
+    }
+
+    /// Return a random `u8`
+    pub fn random_u8(&mut self) -> u8 {
+        let mut buf = [0; 1];
+        self.random(&mut buf);
+        buf[0]
+    }
+
+    /// Return a random `u16`
+    pub fn random_u16(&mut self) -> u16 {
+        let mut buf = [0; 2];
+        self.random(&mut buf);
+        u16::from_le_bytes(buf)
+    }
+
+    /// Return a random `u32`
+    pub fn random_u32(&mut self) -> u32 {
+        let mut buf = [0; 4];
+        self.random(&mut buf);
+        u32::from_le_bytes(buf)
+    }
+
+    /// Return a random `u64`
+    pub fn random_u64(&mut self) -> u64 {
+        let mut buf = [0; 8];
+        self.random(&mut buf);
+        u64::from_le_bytes(buf)
+    }
+}
+
+/// Address of one of the CryptoCell's registers
+///
+/// These are not part of the PAC, which only describes the CRYPTOCELL
+/// peripheral's ENABLE register.
+fn register(offset: usize) -> *mut u32 {
+    (CRYPTOCELL_S::ptr() as usize + CC_REGS_OFFSET + offset) as *mut u32
+}
+
+impl RngCore for Rng {
+    fn next_u32(&mut self) -> u32 {
+        self.random_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.random_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.random(dest)
+    }
+
+    /// Fill `dest`, or return an error if the TRNG doesn't deliver
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+        self.try_random(dest)
+    }
+}
+
+impl CryptoRng for Rng {}
@@ -2,6 +2,10 @@
 
 use core::ops::Deref;
 
+use embedded_hal::blocking::delay::{DelayMs, DelayUs};
+use embedded_hal::timer;
+use void::Void;
+
 
 #[cfg(feature="9160")]
 use crate::target::{rtc0_ns as rtc0, Interrupt, NVIC, RTC0_NS as RTC0, RTC1_NS as RTC1};
@@ -12,6 +16,10 @@ use crate::target::{rtc0, Interrupt, NVIC, RTC0, RTC1};
 #[cfg(any(feature = "52840", feature="52832"))]
 use crate::target::RTC2;
 
+use crate::clocks::{Frozen, LfOscStarted, LFCLK_FREQ};
+use crate::time::{Hertz, MilliSeconds};
+use crate::wait::wait_for_event;
+
 // Zero Size Type State structs
 
 /// The RTC has been stopped
@@ -20,21 +28,59 @@ pub struct Stopped;
 pub struct Started;
 
 /// An opaque high level interface to an RTC peripheral
+///
+/// Once started, the RTC can be used as a `CountDown` or for `DelayMs` and
+/// `DelayUs`, which all use CC[0]. As it runs from the LFCLK, delays are low
+/// power, but only have a resolution of one tick, 30.5 µs at most.
 pub struct Rtc<T, M> {
     periph: T,
     _mode: M,
+    /// Period of the running countdown in ticks, 0 if there is none
+    period: u32,
 }
 
 impl<T> Rtc<T, Stopped>
 where
     T: Instance,
 {
-    pub fn new(rtc: T) -> Self {
+    /// Create the interface
+    ///
+    /// Requires the low frequency clock to be started.
+    pub fn new<H, L>(rtc: T, _clocks: Frozen<H, L, LfOscStarted>) -> Self {
         Rtc {
             periph: rtc,
             _mode: Stopped,
+            period: 0,
         }
     }
+
+    /// Create the interface with the prescaler closest to the given tick
+    /// frequency
+    ///
+    /// Returns the interface and the tick frequency actually achieved. The
+    /// frequency must be between 8 Hz and 32_768 Hz.
+    pub fn new_with_frequency<H, L, F>(
+        rtc: T,
+        clocks: Frozen<H, L, LfOscStarted>,
+        frequency: F,
+    ) -> Result<(Self, Hertz), Error>
+    where
+        F: Into<Hertz>,
+    {
+        let Hertz(frequency) = frequency.into();
+        if frequency < LFCLK_FREQ >> 12 || frequency > LFCLK_FREQ {
+            return Err(Error::FrequencyOutOfRange);
+        }
+
+        // Round to the nearest divisor
+        let divisor = (LFCLK_FREQ + frequency / 2) / frequency;
+
+        let mut rtc = Rtc::new(rtc, clocks);
+        rtc.set_prescaler(divisor - 1)?;
+        let frequency = rtc.frequency();
+
+        Ok((rtc, frequency))
+    }
 }
 
 /// Interrupts/Events that can be generated by the RTCn peripheral
@@ -67,6 +113,7 @@ where
         Rtc {
             periph: self.periph,
             _mode: Started,
+            period: self.period,
         }
     }
 
@@ -78,6 +125,7 @@ where
         Rtc {
             periph: self.periph,
             _mode: Stopped,
+            period: self.period,
         }
     }
 
@@ -199,16 +247,74 @@ where
         Ok(())
     }
 
+    /// Set the compare value of a given register as the duration after the
+    /// counter was cleared, rounded up to whole ticks
+    pub fn set_compare_duration<D>(&mut self, reg: RtcCompareReg, duration: D) -> Result<(), Error>
+    where
+        D: Into<MilliSeconds>,
+    {
+        let MilliSeconds(millis) = duration.into();
+        let ticks = self.millis_to_ticks(millis);
+        if ticks > u64::from(COUNTER_MASK) {
+            return Err(Error::CompareOutOfRange);
+        }
+
+        self.set_compare(reg, ticks as u32)
+    }
+
     /// Obtain the current value of the Real Time Counter, 24 bits of range
     pub fn get_counter(&self) -> u32 {
         self.periph.counter.read().bits()
     }
 
+    /// Obtain the duration since the counter was cleared, rounded down to
+    /// whole milliseconds
+    ///
+    /// Like the counter itself, this wraps around after 2^24 ticks.
+    pub fn get_counter_duration(&self) -> MilliSeconds {
+        let ticks = u64::from(self.get_counter());
+        let millis = ticks * 1_000 * u64::from(self.prescaler() + 1) / u64::from(LFCLK_FREQ);
+        MilliSeconds(millis as u32)
+    }
+
+    /// The tick frequency resulting from the configured prescaler
+    pub fn frequency(&self) -> Hertz {
+        Hertz(LFCLK_FREQ / (self.prescaler() + 1))
+    }
+
+    /// Reset the counter to zero
+    pub fn clear(&mut self) {
+        self.periph.tasks_clear.write(|w| unsafe { w.bits(1) });
+    }
+
+    /// Set the counter to 0xFFFFF0, 16 ticks before it overflows
+    ///
+    /// Useful for testing the handling of the counter's wraparound.
+    pub fn trigger_overflow(&mut self) {
+        self.periph.tasks_trigovrflw.write(|w| unsafe { w.bits(1) });
+    }
+
     /// Destructure the high level interface. Does not reset any configuration made
     /// to the given RTC peripheral
     pub fn release(self) -> T {
         self.periph
     }
+
+    fn prescaler(&self) -> u32 {
+        self.periph.prescaler.read().bits()
+    }
+
+    /// Convert milliseconds to a number of ticks, rounding up
+    fn millis_to_ticks(&self, millis: u32) -> u64 {
+        let divisor = 1_000 * u64::from(self.prescaler() + 1);
+        (u64::from(millis) * u64::from(LFCLK_FREQ) + divisor - 1) / divisor
+    }
+
+    /// Convert microseconds to a number of ticks, rounding up
+    fn micros_to_ticks(&self, micros: u32) -> u64 {
+        let divisor = 1_000_000 * u64::from(self.prescaler() + 1);
+        (u64::from(micros) * u64::from(LFCLK_FREQ) + divisor - 1) / divisor
+    }
 }
 
 /// Error types associated with the RTC peripheral interface
@@ -216,6 +322,8 @@ where
 pub enum Error {
     PrescalerOutOfRange,
     CompareOutOfRange,
+    FrequencyOutOfRange,
+    NoFreeAlarm,
 }
 
 impl<T> Rtc<T, Stopped>
@@ -236,23 +344,496 @@ where
 }
 
 
+impl<T> Rtc<T, Started>
+where
+    T: Instance,
+{
+    /// Program CC[0] to fire `ticks` after the current COUNTER value
+    fn arm(&mut self, ticks: u32) {
+        // Writing COUNTER or COUNTER + 1 to CC[n] may not trigger a COMPARE
+        // event, and the COUNTER may tick once more before CC[0] is written,
+        // so the shortest countdown is three ticks.
+        let ticks = if ticks < 3 { 3 } else { ticks };
+
+        self.periph.events_compare[0].reset();
+        loop {
+            let counter = self.periph.counter.read().bits();
+            let target = (counter + ticks) & COUNTER_MASK;
+            self.periph.cc[0].write(|w| unsafe { w.bits(target) });
+
+            // If this was interrupted for long enough that the COUNTER caught
+            // up anyway, the event may not come. Start over from the current
+            // COUNTER value.
+            let remaining = target.wrapping_sub(self.periph.counter.read().bits()) & COUNTER_MASK;
+            if remaining >= 2 && remaining <= ticks {
+                break;
+            }
+        }
+        self.periph.evtenset.write(|w| w.compare0().set());
+    }
+
+    /// Sleep until the CC[0] COMPARE event occurs
+    fn sleep_until_compare(&mut self) {
+        let periph = &self.periph;
+        wait_for_event(periph, T::INTERRUPT, 1 << 16, || {
+            periph.events_compare[0].read().bits() != 0
+        });
+        self.periph.events_compare[0].reset();
+    }
+
+    /// Sleep for the given number of ticks
+    ///
+    /// Cancels any running countdown, as both use CC[0]. Delays longer than
+    /// the COUNTER range are split up.
+    fn delay_ticks(&mut self, ticks: u64) {
+        self.period = 0;
+
+        // `arm` enables the event, restore its previous setting afterwards
+        let event_enabled = self.periph.evtenset.read().compare0().bit_is_set();
+
+        let mut remaining = ticks;
+        while remaining > 0 {
+            // Leave some room for the time between reading the COUNTER and
+            // writing CC[0]
+            let ticks = if remaining > u64::from(HALF_COUNTER) {
+                HALF_COUNTER
+            } else {
+                remaining as u32
+            };
+
+            self.arm(ticks);
+            self.sleep_until_compare();
+            remaining -= u64::from(ticks);
+        }
+
+        if !event_enabled {
+            self.periph.evtenclr.write(|w| w.compare0().clear());
+        }
+    }
+}
+
+/// Mask of the 24 bit COUNTER and CC registers
+const COUNTER_MASK: u32 = 0xFF_FFFF;
+
+impl<T> timer::CountDown for Rtc<T, Started>
+where
+    T: Instance,
+{
+    type Time = MilliSeconds;
+
+    /// Start the countdown
+    ///
+    /// The countdown restarts after each period. Panics if the duration
+    /// exceeds the 24 bit COUNTER range at the configured prescaler, which is
+    /// 512 seconds with a prescaler of 0.
+    fn start<Time>(&mut self, duration: Time)
+    where
+        Time: Into<Self::Time>,
+    {
+        let MilliSeconds(millis) = duration.into();
+        let ticks = self.millis_to_ticks(millis);
+        assert!(ticks <= u64::from(COUNTER_MASK));
+
+        // `arm` extends shorter periods to three ticks
+        self.period = if ticks < 3 { 3 } else { ticks as u32 };
+        self.arm(self.period);
+    }
+
+    /// Wait for the current period to end
+    ///
+    /// Will return `Err(nb::Error::WouldBlock)` while the period hasn't ended
+    /// yet. Once it has, the next period is started relative to the end of
+    /// this one and `Ok(())` is returned.
+    fn wait(&mut self) -> nb::Result<(), Void> {
+        if self.period == 0 || self.periph.events_compare[0].read().bits() == 0 {
+            return Err(nb::Error::WouldBlock);
+        }
+        self.periph.events_compare[0].reset();
+
+        let last = self.periph.cc[0].read().bits();
+        let counter = self.periph.counter.read().bits();
+        let late = counter.wrapping_sub(last) & COUNTER_MASK;
+
+        if late + 3 <= self.period {
+            let next = (last + self.period) & COUNTER_MASK;
+            self.periph.cc[0].write(|w| unsafe { w.bits(next) });
+
+            // As in `arm`, the COUNTER may have caught up with `next` before
+            // CC[0] was written, in which case the event may not come
+            let remaining = next.wrapping_sub(self.periph.counter.read().bits()) & COUNTER_MASK;
+            if remaining < 2 || remaining > self.period {
+                self.arm(3);
+            }
+        } else {
+            // One or more periods were missed entirely
+            self.arm(3);
+        }
+
+        Ok(())
+    }
+}
+
+impl<T> timer::Periodic for Rtc<T, Started> where T: Instance {}
+
+impl<T> DelayMs<u32> for Rtc<T, Started>
+where
+    T: Instance,
+{
+    /// Sleep for the given number of milliseconds
+    ///
+    /// The CPU sleeps in WFE until the delay is over. Cancels any running
+    /// countdown, as both use CC[0].
+    ///
+    /// If the RTC interrupt is enabled, its handler must not clear the
+    /// COMPARE[0] event while this sleeps, or it never returns. The same
+    /// applies to the other `DelayMs` and `DelayUs` implementations.
+    fn delay_ms(&mut self, ms: u32) {
+        let ticks = self.millis_to_ticks(ms);
+        self.delay_ticks(ticks);
+    }
+}
+
+impl<T> DelayMs<u16> for Rtc<T, Started>
+where
+    T: Instance,
+{
+    fn delay_ms(&mut self, ms: u16) {
+        self.delay_ms(u32::from(ms))
+    }
+}
+
+impl<T> DelayMs<u8> for Rtc<T, Started>
+where
+    T: Instance,
+{
+    fn delay_ms(&mut self, ms: u8) {
+        self.delay_ms(u32::from(ms))
+    }
+}
+
+impl<T> DelayUs<u32> for Rtc<T, Started>
+where
+    T: Instance,
+{
+    /// Sleep for the given number of microseconds
+    ///
+    /// The delay is rounded up to whole ticks. The CPU sleeps in WFE until it
+    /// is over. Cancels any running countdown, as both use CC[0].
+    fn delay_us(&mut self, us: u32) {
+        let ticks = self.micros_to_ticks(us);
+        self.delay_ticks(ticks);
+    }
+}
+
+impl<T> DelayUs<u16> for Rtc<T, Started>
+where
+    T: Instance,
+{
+    fn delay_us(&mut self, us: u16) {
+        self.delay_us(u32::from(us))
+    }
+}
+
+impl<T> DelayUs<u8> for Rtc<T, Started>
+where
+    T: Instance,
+{
+    fn delay_us(&mut self, us: u8) {
+        self.delay_us(u32::from(us))
+    }
+}
+
+
+/// Maximum number of alarms an `RtcTimer` can have pending at once
+pub const MAX_ALARMS: usize = 8;
+
+/// Half of the COUNTER range, in ticks
+const HALF_COUNTER: u32 = 1 << 23;
+
+/// A 64 bit tick counter with software alarms, built on an RTC instance
+///
+/// Runs from the LFCLK, so it keeps counting while the HFCLK is off. The RTC
+/// interrupt must be unmasked and `on_interrupt` must be called from its
+/// handler, both to extend the 24 bit COUNTER on overflow and to fire alarms.
+///
+/// Up to `MAX_ALARMS` alarms can be pending. The earliest of them are
+/// programmed into the RTC's compare registers, the rest are moved there as
+/// registers become free.
+pub struct RtcTimer<T> {
+    periph: T,
+    periods: u32,
+    alarms: [Option<u64>; MAX_ALARMS],
+}
+
+/// Identifies an alarm scheduled on an `RtcTimer`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AlarmId(u8);
+
+/// The alarms that fired during a call to `RtcTimer::on_interrupt`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FiredAlarms(u8);
+
+impl FiredAlarms {
+    pub fn contains(&self, id: AlarmId) -> bool {
+        self.0 & (1 << id.0) != 0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+}
+
+impl Iterator for FiredAlarms {
+    type Item = AlarmId;
+
+    fn next(&mut self) -> Option<AlarmId> {
+        if self.0 == 0 {
+            return None;
+        }
+
+        let id = self.0.trailing_zeros() as u8;
+        self.0 &= !(1 << id);
+        Some(AlarmId(id))
+    }
+}
+
+impl<T> RtcTimer<T>
+where
+    T: Instance,
+{
+    /// Start counting from zero, using the prescaler configured on `rtc`
+    ///
+    /// If access to the NVIC is not provided, the interrupt must ALSO be enabled
+    /// there outside of this function (e.g. manually call `nvic.enable`, or through
+    /// the use of RTFM).
+    pub fn new(rtc: Rtc<T, Stopped>, nvic: Option<&mut NVIC>) -> Self {
+        let periph = rtc.release();
+
+        periph.tasks_clear.write(|w| unsafe { w.bits(1) });
+        periph.events_ovrflw.reset();
+        for event in &periph.events_compare[..T::CC_COUNT] {
+            event.reset();
+        }
+        periph.intenset.write(|w| w.ovrflw().set());
+        periph.tasks_start.write(|w| unsafe { w.bits(1) });
+
+        if let Some(_nvic) = nvic {
+            unsafe { NVIC::unmask(T::INTERRUPT) };
+        }
+
+        RtcTimer {
+            periph,
+            periods: 0,
+            alarms: [None; MAX_ALARMS],
+        }
+    }
+
+    /// Return the number of ticks since the timer was started
+    pub fn now(&self) -> u64 {
+        let counter = self.periph.counter.read().bits();
+
+        // If the overflow hasn't been handled yet, account for it here. An
+        // overflow event that is newer than the COUNTER value just read
+        // leaves the COUNTER in its upper half.
+        let overflowed = self.periph.events_ovrflw.read().bits() != 0
+            && counter < HALF_COUNTER;
+        let periods = self.periods + overflowed as u32;
+
+        (u64::from(periods) << 24) + u64::from(counter)
+    }
+
+    /// The RTC's tick frequency, based on the configured prescaler
+    pub fn frequency(&self) -> Hertz {
+        Hertz(LFCLK_FREQ / (self.prescaler() + 1))
+    }
+
+    /// Convert a number of ticks to milliseconds, rounding down
+    pub fn ticks_to_millis(&self, ticks: u64) -> u64 {
+        ticks * 1_000 * u64::from(self.prescaler() + 1) / u64::from(LFCLK_FREQ)
+    }
+
+    /// Convert milliseconds to a number of ticks, rounding up
+    ///
+    /// Rounding up ensures an alarm never fires early.
+    pub fn millis_to_ticks(&self, millis: u64) -> u64 {
+        let divisor = 1_000 * u64::from(self.prescaler() + 1);
+        (millis * u64::from(LFCLK_FREQ) + divisor - 1) / divisor
+    }
+
+    /// Schedule an alarm for when `now` reaches `deadline`
+    ///
+    /// A deadline in the past fires on the next call to `on_interrupt`, which
+    /// is triggered right away. A deadline less than three ticks away may
+    /// fire up to two ticks late, as the compare event can't be programmed
+    /// any closer.
+    pub fn schedule_at(&mut self, deadline: u64) -> Result<AlarmId, Error> {
+        let index = self
+            .alarms
+            .iter()
+            .position(|alarm| alarm.is_none())
+            .ok_or(Error::NoFreeAlarm)?;
+
+        self.alarms[index] = Some(deadline);
+        self.program_alarms();
+
+        Ok(AlarmId(index as u8))
+    }
+
+    /// Schedule an alarm in the given number of ticks
+    pub fn schedule_in(&mut self, ticks: u64) -> Result<AlarmId, Error> {
+        let deadline = self.now() + ticks;
+        self.schedule_at(deadline)
+    }
+
+    /// Schedule an alarm in the given number of milliseconds
+    pub fn schedule_in_millis(&mut self, millis: u64) -> Result<AlarmId, Error> {
+        let ticks = self.millis_to_ticks(millis);
+        self.schedule_in(ticks)
+    }
+
+    /// Cancel an alarm that hasn't fired yet
+    ///
+    /// Returns whether the alarm was still pending.
+    pub fn cancel(&mut self, id: AlarmId) -> bool {
+        let pending = self.alarms[id.0 as usize].take().is_some();
+        if pending {
+            self.program_alarms();
+        }
+        pending
+    }
+
+    /// Handle the RTC interrupt
+    ///
+    /// Must be called from the interrupt handler of `T`. Returns the alarms
+    /// that have fired, which become free for scheduling again.
+    pub fn on_interrupt(&mut self) -> FiredAlarms {
+        if self.periph.events_ovrflw.read().bits() != 0 {
+            self.periph.events_ovrflw.reset();
+            self.periods += 1;
+        }
+        for event in &self.periph.events_compare[..T::CC_COUNT] {
+            event.reset();
+        }
+
+        let now = self.now();
+        let mut fired = 0;
+        for (index, alarm) in self.alarms.iter_mut().enumerate() {
+            match *alarm {
+                Some(deadline) if deadline <= now => {
+                    *alarm = None;
+                    fired |= 1 << index;
+                }
+                _ => {}
+            }
+        }
+
+        self.program_alarms();
+
+        FiredAlarms(fired)
+    }
+
+    /// Stop counting, discarding all alarms
+    pub fn free(self) -> Rtc<T, Stopped> {
+        self.periph.tasks_stop.write(|w| unsafe { w.bits(1) });
+        self.periph
+            .intenclr
+            .write(|w| unsafe { w.bits(0xFFFF_FFFF) });
+
+        Rtc {
+            periph: self.periph,
+            _mode: Stopped,
+            period: 0,
+        }
+    }
+
+    fn prescaler(&self) -> u32 {
+        self.periph.prescaler.read().bits()
+    }
+
+    /// Program the earliest alarms into the compare registers
+    fn program_alarms(&mut self) {
+        // Sort the pending alarms by deadline
+        let mut order = [0; MAX_ALARMS];
+        let mut pending = 0;
+        for (index, alarm) in self.alarms.iter().enumerate() {
+            if let Some(deadline) = *alarm {
+                let mut i = pending;
+                while i > 0 && self.alarms[order[i - 1]].unwrap() > deadline {
+                    order[i] = order[i - 1];
+                    i -= 1;
+                }
+                order[i] = index;
+                pending += 1;
+            }
+        }
+
+        let now = self.now();
+        let mut due = false;
+        for cc in 0..T::CC_COUNT {
+            let inten = 1 << (16 + cc);
+            let deadline = if cc < pending {
+                self.alarms[order[cc]]
+            } else {
+                None
+            };
+
+            match deadline {
+                Some(deadline) if deadline <= now => due = true,
+                // The compare event is only reliable for values at least two
+                // ticks ahead of the COUNTER, which may tick once more before
+                // CC[n] is written. Closer deadlines are postponed to three
+                // ticks from now. Deadlines that are further away than the
+                // COUNTER's range are programmed after one of the next
+                // overflows.
+                Some(deadline) if deadline - now < 1 << 24 => {
+                    let target = deadline.max(now + 3);
+                    self.periph.cc[cc].write(|w| unsafe { w.bits(target as u32 & COUNTER_MASK) });
+                    self.periph.intenset.write(|w| unsafe { w.bits(inten) });
+
+                    // If this was interrupted for long enough that the
+                    // COUNTER caught up anyway, the event may not come.
+                    // Handle the alarm in the interrupt handler, which
+                    // programs it again.
+                    if target < self.now() + 2 {
+                        due = true;
+                    }
+                }
+                _ => self.periph.intenclr.write(|w| unsafe { w.bits(inten) }),
+            }
+        }
+
+        if due {
+            NVIC::pend(T::INTERRUPT);
+        }
+    }
+}
+
+
 /// Implemented by all RTC instances
 pub trait Instance: Deref<Target = rtc0::RegisterBlock> {
     /// This interrupt associated with this RTC instance
     const INTERRUPT: Interrupt;
+
+    /// The number of compare registers of this RTC instance
+    const CC_COUNT: usize;
 }
 
 macro_rules! impl_instance {
-    ($($name:ident,)*) => {
+    ($($name:ident: $cc_count:expr,)*) => {
         $(
             impl Instance for $name {
                 const INTERRUPT: Interrupt = Interrupt::$name;
+                const CC_COUNT: usize = $cc_count;
             }
         )*
     }
 }
 
-impl_instance!(RTC0, RTC1,);
+#[cfg(not(feature="9160"))]
+impl_instance!(RTC0: 3, RTC1: 4,);
+
+#[cfg(feature="9160")]
+impl_instance!(RTC0: 4, RTC1: 4,);
 
 #[cfg(any(feature = "52840", feature="52832"))]
-impl_instance!(RTC2,);
+impl_instance!(RTC2: 4,);
@@ -1,30 +1,105 @@
 #[cfg(feature = "9160")]
-use crate::target::{saadc_ns as saadc, SAADC_NS as SAADC};
+use crate::target::{saadc_ns as saadc, Interrupt, NVIC, SAADC_NS as SAADC};
 
 #[cfg(not(feature = "9160"))]
-use crate::target::{saadc, SAADC};
+use crate::target::{saadc, Interrupt, NVIC, SAADC};
 
 use crate::gpio::{Floating, Input};
+use crate::wait::wait_for_event;
 use core::{
     hint::unreachable_unchecked,
     sync::atomic::{compiler_fence, Ordering::SeqCst},
 };
 use embedded_hal::adc::{Channel, OneShot};
 
+#[cfg(feature = "async")]
+use crate::waker::{poll_fn, OnDrop};
+#[cfg(feature = "async")]
+use core::task::Poll;
+
 pub use saadc::{
     ch::config::{GAIN_A as Gain, REFSEL_A as Reference, RESP_A as Resistor, TACQ_A as Time},
     oversample::OVERSAMPLE_A as Oversample,
     resolution::VAL_A as Resolution,
 };
 
-// Only 1 channel is allowed right now, a discussion needs to be had as to how
-// multiple channels should work (See "scan mode" in the datasheet)
-// Issue: https://github.com/nrf-rs/nrf52-hal/issues/82
+/// Errors reported by the SAADC driver
+#[derive(Debug, PartialEq, Eq)]
+pub enum Error {
+    /// EasyDMA transferred a different number of results than expected, e.g.
+    /// because the conversion was stopped before all results were written
+    DmaAmountMismatch,
+    /// A conversion or calibration is already in progress
+    Busy,
+    /// No channel has been enabled through `Saadc::enable_channel`
+    NoChannelsEnabled,
+    /// A result buffer is empty, too small, too large, or its length isn't
+    /// a multiple of the number of enabled channels
+    InvalidBuffer,
+    /// The internal sample rate is out of range, or more than one channel is
+    /// enabled
+    InvalidSampleRate,
+}
+
+/// Connect a PSELP or PSELN register to an input, as returned by
+/// `Channel::channel`, or disconnect it if `None` is given
+macro_rules! select_input {
+    ($reg:expr, $field:ident, $input:expr) => {
+        match $input {
+            None => $reg.write(|w| w.$field().nc()),
+            Some(0) => $reg.write(|w| w.$field().analog_input0()),
+            Some(1) => $reg.write(|w| w.$field().analog_input1()),
+            Some(2) => $reg.write(|w| w.$field().analog_input2()),
+            Some(3) => $reg.write(|w| w.$field().analog_input3()),
+            Some(4) => $reg.write(|w| w.$field().analog_input4()),
+            Some(5) => $reg.write(|w| w.$field().analog_input5()),
+            Some(6) => $reg.write(|w| w.$field().analog_input6()),
+            Some(7) => $reg.write(|w| w.$field().analog_input7()),
+            // The register only contains this field, and 9 selects VDD on all
+            // supported chips
+            Some(VDD_ID) => $reg.write(|w| unsafe { w.bits(9) }),
+            #[cfg(feature = "52840")]
+            Some(VDDHDIV5_ID) => $reg.write(|w| unsafe { w.bits(0x0D) }),
+            // This can never happen the only analog inputs have already been defined
+            // PAY CLOSE ATTENTION TO ANY CHANGES TO THIS MACRO OR THE `channel_mappings!` MACRO
+            _ => unsafe { unreachable_unchecked() },
+        }
+    };
+}
+
+/// Channel ID of `InternalVdd`
+const VDD_ID: u8 = 8;
+
+/// Channel ID of `InternalVddHdiv5`
+#[cfg(feature = "52840")]
+const VDDHDIV5_ID: u8 = 9;
 
+/// Interface to the SAADC peripheral
+///
+/// `OneShot::read` converts a single input using channel 0 and the
+/// `SaadcConfig` passed to `new`, and disconnects channel 0 again when done.
+/// Up to eight channels, each with its own `ChannelConfig`, can instead be
+/// sampled in one sequence using `scan` (see "scan mode" in the datasheet).
+///
+/// Both share the channel registers, so when mixing the two:
+/// - a one-shot read reprograms the input of channel 0 and leaves it
+///   disconnected, so channel 0 doesn't stay enabled for `scan`
+/// - `enable_channel(0, ..)` replaces the configuration used by the one-shot
+///   reads until the `Saadc` is created again
+/// - the channels enabled for `scan` are converted along with every one-shot
+///   read, which only keeps the result of channel 0 but takes as long as a
+///   scan
+///
+/// Use indices 1 to 7 for `scan`, and disable them before one-shot reads
+/// where the conversion time matters.
 pub struct Saadc(SAADC);
 
 impl Saadc {
-    pub fn new(saadc: SAADC, config: SaadcConfig) -> Self {
+    /// Configure the SAADC and calibrate its offset
+    ///
+    /// Returns `Error::Busy` if `saadc` is still converting or calibrating,
+    /// e.g. after it was released by `free` in the middle of a conversion.
+    pub fn new(saadc: SAADC, config: SaadcConfig) -> Result<Self, Error> {
         // The write enums do not implement clone/copy/debug, only the
         // read ones, hence the need to pull out and move the values
         let SaadcConfig {
@@ -36,7 +111,8 @@ impl Saadc {
             time,
         } = config;
 
-        saadc.enable.write(|w| w.enable().enabled());
+        // The SAADC is only enabled during conversions, to save current
+        saadc.enable.write(|w| w.enable().disabled());
         saadc.resolution.write(|w| w.val().variant(resolution));
         saadc
             .oversample
@@ -61,11 +137,375 @@ impl Saadc {
         });
         saadc.ch[0].pseln.write(|w| w.pseln().nc());
 
-        // Calibrate
-        saadc.tasks_calibrateoffset.write(|w| unsafe { w.bits(1) });
-        while saadc.events_calibratedone.read().bits() == 0 {}
+        let mut saadc = Saadc(saadc);
+        saadc.calibrate()?;
+
+        Ok(saadc)
+    }
+
+    /// Calibrate the offset of the SAADC
+    ///
+    /// This is done once by `new`. The product specification recommends
+    /// calibrating again whenever the temperature has changed by more than
+    /// 10 °C, e.g. as measured by `temp::Temp`.
+    ///
+    /// The SAADC interrupt handler must not clear the CALIBRATEDONE or STOPPED
+    /// event while this blocks, or it never returns.
+    pub fn calibrate(&mut self) -> Result<(), Error> {
+        self.power_up()?;
+
+        self.0.tasks_calibrateoffset.write(|w| unsafe { w.bits(1) });
+        let saadc = &self.0;
+        wait_for_event(saadc, Interrupt::SAADC, 1 << 4, || {
+            saadc.events_calibratedone.read().bits() != 0
+        });
+        self.0.events_calibratedone.reset();
+
+        self.power_down();
+
+        Ok(())
+    }
+
+    /// Return the raw interface to the underlying SAADC peripheral
+    pub fn free(self) -> SAADC {
+        self.0
+    }
+
+    /// Enable the SAADC for a conversion or calibration
+    fn power_up(&mut self) -> Result<(), Error> {
+        if self.0.status.read().status().is_busy() {
+            return Err(Error::Busy);
+        }
 
-        Saadc(saadc)
+        self.0.enable.write(|w| w.enable().enabled());
+
+        Ok(())
+    }
+
+    /// Stop and disable the SAADC, to save current between conversions
+    fn power_down(&mut self) {
+        self.0.tasks_stop.write(|w| unsafe { w.bits(1) });
+        let saadc = &self.0;
+        wait_for_event(saadc, Interrupt::SAADC, 1 << 5, || {
+            saadc.events_stopped.read().bits() != 0
+        });
+        self.0.events_stopped.reset();
+
+        self.0.enable.write(|w| w.enable().disabled());
+    }
+
+    /// Configure channel `index` (0 to 7) to sample `pin` during `scan`
+    ///
+    /// Each channel has its own gain, reference, acquisition time and
+    /// resistor ladder. The resolution and oversampling set through the
+    /// `SaadcConfig` passed to `new` apply to all channels.
+    ///
+    /// Panics if `index` is larger than 7.
+    pub fn enable_channel<PIN>(&mut self, index: usize, _pin: &mut PIN, config: ChannelConfig)
+    where
+        PIN: Channel<Saadc, ID = u8>,
+    {
+        self.configure_channel(index, config, false);
+        select_input!(self.0.ch[index].pseln, pseln, None);
+        select_input!(self.0.ch[index].pselp, pselp, Some(PIN::channel()));
+    }
+
+    /// Configure channel `index` (0 to 7) to sample the difference between
+    /// `pos` and `neg` during `scan`
+    ///
+    /// See `enable_channel`. Differential results are signed and use one bit
+    /// of the resolution for the sign.
+    ///
+    /// Panics if `index` is larger than 7.
+    pub fn enable_differential_channel<P, N>(
+        &mut self,
+        index: usize,
+        _pos: &mut P,
+        _neg: &mut N,
+        config: ChannelConfig,
+    ) where
+        P: Channel<Saadc, ID = u8>,
+        N: Channel<Saadc, ID = u8>,
+    {
+        self.configure_channel(index, config, true);
+        select_input!(self.0.ch[index].pseln, pseln, Some(N::channel()));
+        select_input!(self.0.ch[index].pselp, pselp, Some(P::channel()));
+    }
+
+    /// Sample the difference between `pos` and `neg` once
+    ///
+    /// Like `OneShot::read`, this uses channel 0 and the `SaadcConfig` passed
+    /// to `new`.
+    ///
+    /// The SAADC interrupt handler must not clear the END or STOPPED event
+    /// while this blocks, or it never returns.
+    pub fn read_differential<P, N>(&mut self, _pos: &mut P, _neg: &mut N) -> Result<i16, Error>
+    where
+        P: Channel<Saadc, ID = u8>,
+        N: Channel<Saadc, ID = u8>,
+    {
+        self.0.ch[0].config.modify(|_, w| w.mode().diff());
+        select_input!(self.0.ch[0].pseln, pseln, Some(N::channel()));
+
+        let mut val: i16 = 0;
+        let res = self.start_read(P::channel(), &mut val).and_then(|_| {
+            let saadc = &self.0;
+            wait_for_event(saadc, Interrupt::SAADC, 1 << 1, || {
+                saadc.events_end.read().bits() != 0
+            });
+            self.finish_read()
+        });
+
+        // Return channel 0 to single ended mode for `OneShot::read`
+        self.0.ch[0].config.modify(|_, w| w.mode().se());
+        select_input!(self.0.ch[0].pseln, pseln, None);
+
+        res.map(|_| val)
+    }
+
+    /// Convert a raw result of channel `index` into millivolts
+    ///
+    /// Takes the gain, reference and mode configured for the channel and the
+    /// configured resolution into account. When the channel uses the
+    /// `VDD1_4` reference, the result is relative to the supply voltage, which
+    /// must be given in `vdd_mv`. It is ignored for the internal reference.
+    ///
+    /// Panics if `index` is larger than 7.
+    pub fn to_millivolts(&self, index: usize, raw: i16, vdd_mv: u32) -> i32 {
+        let config = self.0.ch[index].config.read();
+
+        // The internal reference is 0.6 V
+        let reference_mv = if config.refsel().bit_is_clear() {
+            600
+        } else {
+            i64::from(vdd_mv) / 4
+        };
+
+        // Gain as a fraction `(numerator, denominator)`, indexed by the
+        // value of the GAIN field
+        const GAINS: [(i64, i64); 8] = [
+            (1, 6),
+            (1, 5),
+            (1, 4),
+            (1, 3),
+            (1, 2),
+            (1, 1),
+            (2, 1),
+            (4, 1),
+        ];
+        let (gain_num, gain_den) = GAINS[config.gain().bits() as usize];
+
+        // RESOLUTION is 0 for 8 bits and goes up in steps of 2 bits. One bit
+        // is used for the sign in differential mode.
+        let mut bits = 8 + 2 * u32::from(self.0.resolution.read().val().bits());
+        if config.mode().bit_is_set() {
+            bits -= 1;
+        }
+
+        // RESULT = V * GAIN / REFERENCE * 2^bits
+        (i64::from(raw) * reference_mv * gain_den / (gain_num << bits)) as i32
+    }
+
+    /// Set the limits of channel `index` (0 to 7)
+    ///
+    /// The channel's LIMITH event is generated whenever a result is above
+    /// `high`, and its LIMITL event whenever a result is below `low`.
+    ///
+    /// Panics if `index` is larger than 7.
+    pub fn set_limits(&mut self, index: usize, low: i16, high: i16) {
+        self.0.ch[index].limit.write(|w|
+            // Both fields are 16 bits wide and hold a two's complement value,
+            // the same representation the results use.
+            unsafe { w.low().bits(low as u16).high().bits(high as u16) });
+    }
+
+    /// Enable the interrupt for a limit event of channel `index` (0 to 7)
+    ///
+    /// If access to the NVIC is not provided, the interrupt must ALSO be enabled
+    /// there outside of this function (e.g. manually call `nvic.enable`, or through
+    /// the use of RTFM).
+    ///
+    /// Panics if `index` is larger than 7.
+    pub fn enable_limit_interrupt(&mut self, index: usize, limit: Limit, nvic: Option<&mut NVIC>) {
+        let mask = limit_inten_mask(index, limit);
+        self.0.intenset.write(|w| unsafe { w.bits(mask) });
+
+        if let Some(_nvic) = nvic {
+            unsafe { NVIC::unmask(Interrupt::SAADC) };
+        }
+    }
+
+    /// Disable the interrupt for a limit event of channel `index` (0 to 7)
+    ///
+    /// Only the interrupt for this event is disabled, so the NVIC is left
+    /// alone.
+    ///
+    /// Panics if `index` is larger than 7.
+    pub fn disable_limit_interrupt(&mut self, index: usize, limit: Limit) {
+        let mask = limit_inten_mask(index, limit);
+        self.0.intenclr.write(|w| unsafe { w.bits(mask) });
+    }
+
+    /// Check whether a limit event of channel `index` (0 to 7) has occurred
+    ///
+    /// Panics if `index` is larger than 7.
+    pub fn is_limit_triggered(&self, index: usize, limit: Limit) -> bool {
+        match limit {
+            Limit::High => self.0.events_ch[index].limith.read().bits() != 0,
+            Limit::Low => self.0.events_ch[index].limitl.read().bits() != 0,
+        }
+    }
+
+    /// Clear a limit event of channel `index` (0 to 7)
+    ///
+    /// Panics if `index` is larger than 7.
+    pub fn clear_limit_event(&mut self, index: usize, limit: Limit) {
+        match limit {
+            Limit::High => self.0.events_ch[index].limith.reset(),
+            Limit::Low => self.0.events_ch[index].limitl.reset(),
+        }
+    }
+
+    /// Apply `config` to channel `index`
+    fn configure_channel(&mut self, index: usize, config: ChannelConfig, differential: bool) {
+        let ChannelConfig {
+            reference,
+            gain,
+            resistor,
+            time,
+        } = config;
+
+        self.0.ch[index].config.write(|w| {
+            let w = w.refsel()
+                .variant(reference)
+                .gain()
+                .variant(gain)
+                .tacq()
+                .variant(time)
+                .resp()
+                .variant(resistor)
+                .resn()
+                .bypass()
+                // Oversampling in scan mode requires burst mode on all
+                // enabled channels
+                .burst()
+                .enabled();
+            if differential {
+                w.mode().diff()
+            } else {
+                w.mode().se()
+            }
+        });
+    }
+
+    /// Stop sampling channel `index` (0 to 7) during `scan`
+    ///
+    /// Panics if `index` is larger than 7.
+    pub fn disable_channel(&mut self, index: usize) {
+        self.0.ch[index].pselp.write(|w| w.pselp().nc());
+    }
+
+    /// Return the number of channels that are sampled during `scan`
+    pub fn enabled_channels(&self) -> usize {
+        self.0.ch.iter().filter(|ch| !ch.pselp.read().pselp().is_nc()).count()
+    }
+
+    /// Sample all enabled channels in a single conversion sequence
+    ///
+    /// The results are written to `results` in channel order, i.e. the result
+    /// of the lowest enabled channel comes first. `results` must have room
+    /// for at least `enabled_channels()` values. Returns the number of
+    /// results written.
+    ///
+    /// The SAADC interrupt handler must not clear the END or STOPPED event
+    /// while this blocks, or it never returns.
+    pub fn scan(&mut self, results: &mut [i16]) -> Result<usize, Error> {
+        let count = self.enabled_channels();
+        if count == 0 {
+            return Err(Error::NoChannelsEnabled);
+        }
+        if results.len() < count {
+            return Err(Error::InvalidBuffer);
+        }
+
+        self.power_up()?;
+
+        self.0
+            .result
+            .ptr
+            .write(|w| unsafe { w.ptr().bits(results.as_mut_ptr() as u32) });
+        self.0
+            .result
+            .maxcnt
+            .write(|w| unsafe { w.maxcnt().bits(count as _) });
+
+        // Conservative compiler fence to prevent starting the ADC before the
+        // pointer and maxcount have been set
+        compiler_fence(SeqCst);
+
+        self.0.tasks_start.write(|w| unsafe { w.bits(1) });
+        self.0.tasks_sample.write(|w| unsafe { w.bits(1) });
+
+        let saadc = &self.0;
+        wait_for_event(saadc, Interrupt::SAADC, 1 << 1, || {
+            saadc.events_end.read().bits() != 0
+        });
+        self.0.events_end.reset();
+
+        self.power_down();
+
+        // Second fence to prevent optimizations creating issues with the
+        // EasyDMA-modified `results`
+        compiler_fence(SeqCst);
+
+        if self.0.result.amount.read().bits() as usize != count {
+            return Err(Error::DmaAmountMismatch);
+        }
+
+        Ok(count)
+    }
+}
+
+/// The limits of a channel, see `Saadc::set_limits`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Limit {
+    /// A result was above the high limit
+    High,
+    /// A result was below the low limit
+    Low,
+}
+
+/// INTEN bit of a limit event
+fn limit_inten_mask(index: usize, limit: Limit) -> u32 {
+    assert!(index < 8);
+
+    // CH[0].LIMITH is bit 6, followed by CH[0].LIMITL and then the other
+    // channels in the same order
+    let bit = 6 + 2 * index as u32 + match limit {
+        Limit::High => 0,
+        Limit::Low => 1,
+    };
+    1 << bit
+}
+
+/// Configuration of a single channel in scan mode, see
+/// `Saadc::enable_channel`
+pub struct ChannelConfig {
+    pub reference: Reference,
+    pub gain: Gain,
+    pub resistor: Resistor,
+    pub time: Time,
+}
+
+// Same channel settings as the default `SaadcConfig`
+impl Default for ChannelConfig {
+    fn default() -> Self {
+        ChannelConfig {
+            reference: Reference::VDD1_4,
+            gain: Gain::GAIN1_4,
+            resistor: Resistor::BYPASS,
+            time: Time::_20US,
+        }
     }
 }
 
@@ -92,31 +532,20 @@ impl Default for SaadcConfig {
     }
 }
 
-impl<PIN> OneShot<Saadc, i16, PIN> for Saadc
-where
-    PIN: Channel<Saadc, ID = u8>,
-{
-    type Error = ();
-    fn read(&mut self, _pin: &mut PIN) -> nb::Result<i16, Self::Error> {
-        match PIN::channel() {
-            0 => self.0.ch[0].pselp.write(|w| w.pselp().analog_input0()),
-            1 => self.0.ch[0].pselp.write(|w| w.pselp().analog_input1()),
-            2 => self.0.ch[0].pselp.write(|w| w.pselp().analog_input2()),
-            3 => self.0.ch[0].pselp.write(|w| w.pselp().analog_input3()),
-            4 => self.0.ch[0].pselp.write(|w| w.pselp().analog_input4()),
-            5 => self.0.ch[0].pselp.write(|w| w.pselp().analog_input5()),
-            6 => self.0.ch[0].pselp.write(|w| w.pselp().analog_input6()),
-            7 => self.0.ch[0].pselp.write(|w| w.pselp().analog_input7()),
-            // This can never happen the only analog pins have already been defined
-            // PAY CLOSE ATTENTION TO ANY CHANGES TO THIS IMPL OR THE `channel_mappings!` MACRO
-            _ => unsafe { unreachable_unchecked() },
-        }
+impl Saadc {
+    /// Select the input of the channel and start a single conversion into
+    /// `val`
+    ///
+    /// The caller must make sure `val` stays valid until the END event.
+    fn start_read(&mut self, channel: u8, val: &mut i16) -> Result<(), Error> {
+        self.power_up()?;
+
+        select_input!(self.0.ch[0].pselp, pselp, Some(channel));
 
-        let mut val: i16 = 0;
         self.0
             .result
             .ptr
-            .write(|w| unsafe { w.ptr().bits(((&mut val) as *mut _) as u32) });
+            .write(|w| unsafe { w.ptr().bits((val as *mut _) as u32) });
         self.0
             .result
             .maxcnt
@@ -129,21 +558,466 @@ where
         self.0.tasks_start.write(|w| unsafe { w.bits(1) });
         self.0.tasks_sample.write(|w| unsafe { w.bits(1) });
 
-        while self.0.events_end.read().bits() == 0 {}
+        Ok(())
+    }
+
+    /// Clean up after a conversion has signalled its END event
+    fn finish_read(&mut self) -> Result<(), Error> {
         self.0.events_end.reset();
 
-        // Will only occur if more than one channel has been enabled
+        self.power_down();
+
+        // Disconnect channel 0 again, so it isn't sampled by `scan`
+        select_input!(self.0.ch[0].pselp, pselp, None);
+
+        // MAXCNT is 1, so the sequence ends after the result of channel 0
+        // even if channels used by `scan` are enabled as well. Anything else
+        // means that no result was written.
         if self.0.result.amount.read().bits() != 1 {
-            return Err(nb::Error::Other(()));
+            return Err(Error::DmaAmountMismatch);
         }
 
         // Second fence to prevent optimizations creating issues with the EasyDMA-modified `val`
         compiler_fence(SeqCst);
 
+        Ok(())
+    }
+}
+
+impl<PIN> OneShot<Saadc, i16, PIN> for Saadc
+where
+    PIN: Channel<Saadc, ID = u8>,
+{
+    type Error = Error;
+    fn read(&mut self, _pin: &mut PIN) -> nb::Result<i16, Self::Error> {
+        let mut val: i16 = 0;
+        self.start_read(PIN::channel(), &mut val)
+            .map_err(nb::Error::Other)?;
+
+        let saadc = &self.0;
+        wait_for_event(saadc, Interrupt::SAADC, 1 << 1, || {
+            saadc.events_end.read().bits() != 0
+        });
+
+        self.finish_read().map_err(nb::Error::Other)?;
+
         Ok(val)
     }
 }
 
+/// What triggers the individual samples in continuous mode
+pub enum SampleTrigger {
+    /// Use the SAADC's internal timer, sampling every `cc` cycles of the
+    /// 16 MHz clock. `cc` must be in the range 80 to 2047. Only a single
+    /// channel may be enabled in this mode.
+    Internal(u16),
+    /// Sample whenever the SAMPLE task is triggered, e.g. through PPI, see
+    /// `Continuous::tasks_sample_address`
+    External,
+}
+
+impl Saadc {
+    /// Start continuous sampling into alternating RAM buffers
+    ///
+    /// Samples all channels enabled through `enable_channel` until a buffer
+    /// is full. `first` is filled first, `second` is queued behind it.
+    /// Completed buffers are returned by `Continuous::take_completed`, and
+    /// handed back through `Continuous::give_next`.
+    ///
+    /// The END_START short moves sampling on to the queued buffer in
+    /// hardware, so no samples are lost as long as a buffer is handed back
+    /// before the one after it is full. Otherwise sampling stalls until one
+    /// is available.
+    ///
+    /// The length of each buffer must be a multiple of the number of
+    /// enabled channels, and at most 32767 samples.
+    ///
+    /// The SAADC interrupt handler must not clear the STARTED event before
+    /// this returns, or it never does.
+    pub fn continuous(
+        self,
+        trigger: SampleTrigger,
+        first: &'static mut [i16],
+        second: &'static mut [i16],
+    ) -> Result<Continuous, (Error, Saadc, &'static mut [i16], &'static mut [i16])> {
+        let channels = self.enabled_channels();
+        if channels == 0 {
+            return Err((Error::NoChannelsEnabled, self, first, second));
+        }
+        let valid_buffer = |buf: &[i16]| {
+            !buf.is_empty() && buf.len() <= 0x7FFF && buf.len() % channels == 0
+        };
+        if !valid_buffer(first) || !valid_buffer(second) {
+            return Err((Error::InvalidBuffer, self, first, second));
+        }
+
+        let internal = match trigger {
+            SampleTrigger::Internal(cc) => {
+                if channels != 1 || cc < 80 || cc > 2047 {
+                    return Err((Error::InvalidSampleRate, self, first, second));
+                }
+                self.0
+                    .samplerate
+                    .write(|w| unsafe { w.cc().bits(cc) }.mode().timers());
+                true
+            }
+            SampleTrigger::External => {
+                self.0.samplerate.write(|w| w.mode().task());
+                false
+            }
+        };
+
+        let mut saadc = self;
+        if let Err(e) = saadc.power_up() {
+            return Err((e, saadc, first, second));
+        }
+
+        let mut continuous = Continuous {
+            saadc,
+            active: None,
+            queued: None,
+            next: Some(second),
+            completed: None,
+            ptr_free: false,
+        };
+        continuous.program(first);
+        continuous.start();
+        continuous.active = Some(first);
+
+        // Wait until `first` has been latched, so that `second` can be
+        // queued right away
+        let saadc = &continuous.saadc.0;
+        wait_for_event(saadc, Interrupt::SAADC, 1 << 0, || {
+            saadc.events_started.read().bits() != 0
+        });
+        continuous.saadc.0.events_started.reset();
+        continuous.ptr_free = true;
+        continuous.stage();
+
+        if internal {
+            // Kicks off the internal timer, which then keeps sampling
+            continuous.saadc.0.tasks_sample.write(|w| unsafe { w.bits(1) });
+        }
+
+        Ok(continuous)
+    }
+}
+
+/// Continuous, double-buffered sampling, see `Saadc::continuous`
+pub struct Continuous {
+    saadc: Saadc,
+    /// The buffer EasyDMA is currently writing to, `None` if sampling has
+    /// stalled
+    active: Option<&'static mut [i16]>,
+    /// The buffer RESULT.PTR points to, started by the END_START short once
+    /// the active one is full. The short is only enabled while there is one.
+    queued: Option<&'static mut [i16]>,
+    /// A buffer waiting for RESULT.PTR to become free
+    next: Option<&'static mut [i16]>,
+    /// The most recently filled buffer
+    completed: Option<&'static mut [i16]>,
+    /// Whether the last START has been acknowledged by STARTED, after which
+    /// RESULT.PTR may be changed without affecting the active buffer
+    ptr_free: bool,
+}
+
+impl Continuous {
+    /// Take the most recently filled buffer, if there is one
+    ///
+    /// This also queues the next buffer once the active one has been
+    /// started, so it must be called from the SAADC interrupt (see
+    /// `enable_interrupt`) or polled frequently enough to not stall
+    /// sampling. It never blocks.
+    ///
+    /// If a filled buffer isn't taken before the next one is full, one of
+    /// them is reused for sampling and its samples are lost.
+    pub fn take_completed(&mut self) -> Option<&'static mut [i16]> {
+        if self.saadc.0.events_end.read().bits() != 0 {
+            self.saadc.0.events_end.reset();
+
+            // Conservative compiler fence to prevent optimizations creating
+            // issues with the EasyDMA-modified buffer
+            compiler_fence(SeqCst);
+
+            let done = self.active.take();
+            if let Some(queued) = self.queued.take() {
+                // Already started by the END_START short. Disable it until
+                // the next buffer has been queued, so that the full buffer
+                // isn't started again.
+                self.saadc.0.shorts.write(|w| w);
+                self.active = Some(queued);
+                self.ptr_free = false;
+            }
+
+            if let Some(done) = done {
+                if let Some(old) = self.completed.take() {
+                    // The previous buffer was never taken, reuse one of them
+                    if self.next.is_none() {
+                        self.next = Some(old);
+                        self.completed = Some(done);
+                    } else {
+                        self.completed = Some(old);
+                        self.next = Some(done);
+                    }
+                } else {
+                    self.completed = Some(done);
+                }
+            }
+        }
+
+        if self.saadc.0.events_started.read().bits() != 0 {
+            self.saadc.0.events_started.reset();
+            self.ptr_free = true;
+        }
+
+        self.stage();
+
+        self.completed.take()
+    }
+
+    /// Hand a buffer back to be filled after the active one
+    ///
+    /// Returns the buffer as an error if there already is a buffer waiting
+    /// to be filled, or if its length isn't valid (see `Saadc::continuous`).
+    pub fn give_next(&mut self, buffer: &'static mut [i16]) -> Result<(), &'static mut [i16]> {
+        let channels = self.saadc.enabled_channels();
+        if self.queued.is_some()
+            || self.next.is_some()
+            || buffer.is_empty()
+            || buffer.len() > 0x7FFF
+            || buffer.len() % channels != 0
+        {
+            return Err(buffer);
+        }
+
+        self.next = Some(buffer);
+        self.stage();
+        Ok(())
+    }
+
+    /// Check whether a limit event of channel `index` (0 to 7) has occurred,
+    /// see `Saadc::set_limits`
+    ///
+    /// Panics if `index` is larger than 7.
+    pub fn is_limit_triggered(&self, index: usize, limit: Limit) -> bool {
+        self.saadc.is_limit_triggered(index, limit)
+    }
+
+    /// Clear a limit event of channel `index` (0 to 7)
+    ///
+    /// Panics if `index` is larger than 7.
+    pub fn clear_limit_event(&mut self, index: usize, limit: Limit) {
+        self.saadc.clear_limit_event(index, limit)
+    }
+
+    /// Return the address of the SAMPLE task, to be used as a PPI task
+    /// endpoint with `SampleTrigger::External`
+    pub fn tasks_sample_address(&self) -> u32 {
+        &self.saadc.0.tasks_sample as *const _ as u32
+    }
+
+    /// Enables the STARTED and END interrupts, fired whenever a buffer has
+    /// been started or is full
+    ///
+    /// If access to the NVIC is not provided, the interrupt must ALSO be enabled
+    /// there outside of this function (e.g. manually call `nvic.enable`, or through
+    /// the use of RTFM).
+    pub fn enable_interrupt(&mut self, nvic: Option<&mut NVIC>) {
+        self.saadc.0.intenset.write(|w| w.started().set().end().set());
+
+        if let Some(_nvic) = nvic {
+            unsafe { NVIC::unmask(Interrupt::SAADC) };
+        }
+    }
+
+    /// Disables the STARTED and END interrupts
+    ///
+    /// If access to the NVIC is not provided, the interrupt must ALSO be disabled
+    /// there outside of this function (e.g. manually call `nvic.disable`, or through
+    /// the use of RTFM).
+    pub fn disable_interrupt(&mut self, nvic: Option<&mut NVIC>) {
+        self.saadc.0.intenclr.write(|w| w.started().clear().end().clear());
+
+        if let Some(_nvic) = nvic {
+            NVIC::mask(Interrupt::SAADC);
+        }
+    }
+
+    /// Stop sampling
+    ///
+    /// Returns the `Saadc`, set back to software triggered sampling and
+    /// disabled, and all buffers that haven't been taken yet: the partially
+    /// filled active buffer, the queued buffer, the buffer waiting to be
+    /// queued and the last completed buffer.
+    ///
+    /// The SAADC interrupt handler must not clear the STOPPED event while this
+    /// blocks, or it never returns.
+    pub fn stop(mut self) -> (Saadc, [Option<&'static mut [i16]>; 4]) {
+        self.saadc.0.shorts.write(|w| w);
+        self.saadc.0.intenclr.write(|w| w.started().clear().end().clear());
+        self.saadc.power_down();
+
+        let saadc = &self.saadc.0;
+        saadc.events_end.reset();
+        saadc.events_started.reset();
+        saadc.samplerate.write(|w| w.mode().task());
+
+        // Conservative compiler fence to prevent optimizations creating
+        // issues with the EasyDMA-modified buffers
+        compiler_fence(SeqCst);
+
+        let buffers = [
+            self.active.take(),
+            self.queued.take(),
+            self.next.take(),
+            self.completed.take(),
+        ];
+        (self.saadc, buffers)
+    }
+
+    /// Move the next buffer into RESULT.PTR, if it is free
+    ///
+    /// The buffer is queued behind the active one, or started right away if
+    /// sampling has stalled.
+    fn stage(&mut self) {
+        if !self.ptr_free || self.queued.is_some() {
+            return;
+        }
+        let next = match self.next.take() {
+            Some(next) => next,
+            None => return,
+        };
+
+        self.program(next);
+        if self.active.is_some() {
+            self.queued = Some(next);
+            self.saadc.0.shorts.write(|w| w.end_start().enabled());
+        } else {
+            self.start();
+            self.active = Some(next);
+        }
+    }
+
+    /// Point RESULT.PTR to `buffer`
+    ///
+    /// The register is double buffered, so this doesn't affect the active
+    /// buffer once its STARTED event has occurred.
+    fn program(&mut self, buffer: &[i16]) {
+        self.saadc
+            .0
+            .result
+            .ptr
+            .write(|w| unsafe { w.ptr().bits(buffer.as_ptr() as u32) });
+        self.saadc
+            .0
+            .result
+            .maxcnt
+            .write(|w| unsafe { w.maxcnt().bits(buffer.len() as _) });
+    }
+
+    /// Trigger the START task
+    ///
+    /// RESULT.PTR must not be changed until the STARTED event has been
+    /// handled by `take_completed`.
+    fn start(&mut self) {
+        // Conservative compiler fence to prevent starting the ADC before the
+        // pointer and maxcount have been set
+        compiler_fence(SeqCst);
+
+        self.saadc.0.tasks_start.write(|w| unsafe { w.bits(1) });
+        self.ptr_free = false;
+    }
+}
+
+#[cfg(feature = "async")]
+impl Saadc {
+    impl_async_instance!(SAADC, saadc::RegisterBlock);
+
+    /// Asynchronous version of `OneShot::read`
+    ///
+    /// Requires `on_interrupt` to be called from the SAADC interrupt handler.
+    /// Dropping the returned future stops the conversion.
+    pub async fn read_async<PIN>(&mut self, _pin: &mut PIN) -> Result<i16, Error>
+    where
+        PIN: Channel<Saadc, ID = u8>,
+    {
+        let mut val: i16 = 0;
+        self.start_read(PIN::channel(), &mut val)?;
+
+        let saadc = &*self.0;
+        let on_drop = OnDrop::new(|| {
+            // The future was dropped before the conversion completed, so
+            // stop it before `val` goes away.
+            saadc.intenclr.write(|w| w.end().clear());
+            saadc.tasks_stop.write(|w| unsafe { w.bits(1) });
+
+            // STOP always generates STOPPED, within a few cycles, so this
+            // doesn't spin for long. The drop can't await the event.
+            while saadc.events_stopped.read().bits() == 0 {}
+            saadc.events_stopped.reset();
+            saadc.events_end.reset();
+            saadc.enable.write(|w| w.enable().disabled());
+            saadc.ch[0].pselp.write(|w| w.pselp().nc());
+        });
+
+        poll_fn(|cx| {
+            Saadc::waker().register(cx.waker());
+            saadc.intenset.write(|w| w.end().set());
+
+            if saadc.events_end.read().bits() != 0 {
+                Poll::Ready(())
+            } else {
+                Poll::Pending
+            }
+        })
+        .await;
+        on_drop.defuse();
+
+        self.finish_read()?;
+
+        Ok(val)
+    }
+}
+
+/// Interrupt handler for the `async` methods of `Saadc`
+///
+/// Must be called from the SAADC interrupt handler.
+#[cfg(feature = "async")]
+pub fn on_interrupt() {
+    let saadc = Saadc::regs();
+
+    if saadc.events_end.read().bits() != 0 {
+        // The event itself is reset by the woken task
+        saadc.intenclr.write(|w| w.end().clear());
+        Saadc::waker().wake();
+    }
+}
+
+/// The supply voltage VDD, as an internal input of the SAADC
+pub struct InternalVdd;
+
+impl Channel<Saadc> for InternalVdd {
+    type ID = u8;
+
+    fn channel() -> u8 {
+        VDD_ID
+    }
+}
+
+/// The high voltage supply VDDH divided by 5, as an internal input of the
+/// SAADC
+#[cfg(feature = "52840")]
+pub struct InternalVddHdiv5;
+
+#[cfg(feature = "52840")]
+impl Channel<Saadc> for InternalVddHdiv5 {
+    type ID = u8;
+
+    fn channel() -> u8 {
+        VDDHDIV5_ID
+    }
+}
+
 macro_rules! channel_mappings {
     ($($n:expr => $pin:path),*) => {
         $(
@@ -1,16 +1,17 @@
 //! HAL interface to the SPIM peripheral
 //!
 //! See product specification, chapter 31.
+use core::mem::ManuallyDrop;
 use core::ops::Deref;
+use core::ptr;
 use core::sync::atomic::{compiler_fence, Ordering::SeqCst};
 
 #[cfg(feature="9160")]
-use crate::target::{spim0_ns as spim0, SPIM0_NS as SPIM0 };
+use crate::target::{spim0_ns as spim0, Interrupt, DPPIC_NS as DPPIC, NVIC, SPIM0_NS as SPIM0 };
 
 #[cfg(not(feature="9160"))]
-use crate::target::{spim0, SPIM0};
+use crate::target::{spim0, Interrupt, NVIC, PPI, SPIM0};
 
-pub use spim0::frequency::FREQUENCY_A as Frequency;
 pub use embedded_hal::spi::{Mode, Phase, Polarity, MODE_0, MODE_1, MODE_2, MODE_3};
 
 use core::iter::repeat_with;
@@ -18,10 +19,20 @@ use core::iter::repeat_with;
 #[cfg(any(feature = "52832", feature = "52840"))]
 use crate::target::{SPIM1, SPIM2};
 
+#[cfg(feature = "52840")]
+use crate::target::SPIM3;
+
 use crate::gpio::{Floating, Input, Output, Pin, PushPull};
 use embedded_hal::digital::v2::OutputPin;
 use crate::target_constants::{EASY_DMA_SIZE, FORCE_COPY_BUFFER_SIZE};
-use crate::{slice_in_ram, DmaSlice};
+use crate::{list_in_ram, slice_in_ram, DmaSlice};
+use crate::timer;
+use crate::wait::wait_for_event;
+
+#[cfg(feature = "async")]
+use crate::waker::{poll_fn, OnDrop};
+#[cfg(feature = "async")]
+use core::task::Poll;
 
 
 /// Interface to a SPIM instance
@@ -97,7 +108,10 @@ where
         )
     }
 
-    pub fn new(spim: T, pins: Pins, frequency: Frequency, mode: Mode, orc: u8) -> Self {
+    pub fn new<F>(spim: T, pins: Pins, frequency: F, mode: Mode, orc: u8) -> Self
+    where
+        F: FrequencyFor<T>,
+    {
         // Select pins
         spim.psel.sck.write(|w| {
             let w = unsafe { w.pin().bits(pins.sck.pin) };
@@ -128,30 +142,67 @@ where
         // Enable SPIM instance
         spim.enable.write(|w| w.enable().enabled());
 
-        // Configure mode
-        spim.config.write(|w| {
+        let mut spim = Spim(spim);
+        spim.set_bit_order(BitOrder::MsbFirst);
+        spim.set_mode(mode);
+        spim.set_frequency(frequency);
+        spim.set_orc(orc);
+
+        spim
+    }
+
+    /// Change the SPI clock frequency
+    ///
+    /// Can be called between transfers, without releasing the pins.
+    pub fn set_frequency<F>(&mut self, frequency: F)
+    where
+        F: FrequencyFor<T>,
+    {
+        self.0.frequency.write(|w|
+            // `FrequencyFor<T>` is only implemented for frequencies that are
+            // valid values for the FREQUENCY register of `T`, see the product
+            // specification.
+            unsafe { w.bits(frequency.bits()) });
+    }
+
+    /// Change the SPI mode (clock polarity and phase)
+    ///
+    /// Can be called between transfers, without releasing the pins.
+    pub fn set_mode(&mut self, mode: Mode) {
+        self.0.config.modify(|_, w| {
             // Can't match on `mode` due to embedded-hal, see https://github.com/rust-embedded/embedded-hal/pull/126
             if mode == MODE_0 {
-                w.order().msb_first().cpol().active_high().cpha().leading()
+                w.cpol().active_high().cpha().leading()
             } else if mode == MODE_1 {
-                w.order().msb_first().cpol().active_high().cpha().trailing()
+                w.cpol().active_high().cpha().trailing()
             } else if mode == MODE_2 {
-                w.order().msb_first().cpol().active_low().cpha().leading()
+                w.cpol().active_low().cpha().leading()
             } else {
-                w.order().msb_first().cpol().active_low().cpha().trailing()
+                w.cpol().active_low().cpha().trailing()
             }
         });
+    }
 
-        // Configure frequency
-        spim.frequency.write(|w| w.frequency().variant(frequency));
+    /// Change the order in which the bits of each byte are shifted out
+    ///
+    /// Can be called between transfers, without releasing the pins.
+    pub fn set_bit_order(&mut self, order: BitOrder) {
+        self.0.config.modify(|_, w| match order {
+            BitOrder::MsbFirst => w.order().msb_first(),
+            BitOrder::LsbFirst => w.order().lsb_first(),
+        });
+    }
 
-        // Set over-read character to `0`
-        spim.orc.write(|w|
-            // The ORC field is 8 bits long, so `0` is a valid value to write
-            // there.
+    /// Change the over-read character
+    ///
+    /// The over-read character is transmitted once all bytes of the TX
+    /// buffer have been sent, while more bytes are being received. Can be
+    /// called between transfers, without releasing the pins.
+    pub fn set_orc(&mut self, orc: u8) {
+        self.0.orc.write(|w|
+            // The ORC field is 8 bits long, so all values of `u8` are valid
+            // to write there.
             unsafe { w.orc().bits(orc) });
-
-        Spim(spim)
     }
 
     /// Internal helper function to setup and execute SPIM DMA transfer
@@ -160,7 +211,25 @@ where
         tx: DmaSlice,
         rx: DmaSlice,
     ) -> Result<(), Error> {
+        self.start_spi_dma_transfer(&tx, &rx);
+
+        // Wait for END event
+        //
+        // This event is triggered once both transmitting and receiving are
+        // done.
+        let spim = &self.0;
+        wait_for_event(spim, T::INTERRUPT, 1 << 6, || {
+            spim.events_end.read().bits() != 0
+        });
 
+        self.finish_spi_dma_transfer(&tx, &rx)
+    }
+
+    /// Internal helper function to setup and start a SPIM DMA transfer
+    ///
+    /// The caller must make sure the memory described by `tx` and `rx` stays
+    /// valid until the transfer has been finished.
+    fn start_spi_dma_transfer(&mut self, tx: &DmaSlice, rx: &DmaSlice) {
         // Conservative compiler fence to prevent optimizations that do not
         // take in to account actions by DMA. The fence has been placed here,
         // before any DMA action has started
@@ -198,13 +267,11 @@ where
         // take in to account actions by DMA. The fence has been placed here,
         // after all possible DMA actions have completed
         compiler_fence(SeqCst);
+    }
 
-        // Wait for END event
-        //
-        // This event is triggered once both transmitting and receiving are
-        // done.
-        while self.0.events_end.read().bits() == 0 {}
-
+    /// Internal helper function to clean up after a SPIM DMA transfer has
+    /// signalled its END event
+    fn finish_spi_dma_transfer(&mut self, tx: &DmaSlice, rx: &DmaSlice) -> Result<(), Error> {
         // Reset the event, otherwise it will always read `1` from now on.
         self.0.events_end.write(|w| w);
 
@@ -222,9 +289,184 @@ where
         Ok(())
     }
 
+    /// Start a non-blocking transfer
+    ///
+    /// Takes ownership of the SPIM and both buffers, starts the DMA transfer
+    /// and returns immediately. Use the returned `Transfer` to check whether
+    /// the transfer has completed (e.g. from the SPIM interrupt, see
+    /// `enable_interrupt`) and to get the SPIM and the buffers back.
+    ///
+    /// Transmits all bytes in `tx_buffer` and receives bytes until
+    /// `rx_buffer` is full. Extra outgoing bytes will be filled with the
+    /// `orc` value. Either buffer may be empty.
+    ///
+    /// Both buffers must reside in RAM and have a length of at most 255 bytes
+    /// on the nRF52832 and at most 65535 bytes on the nRF52840. Chip select
+    /// is not handled by this method.
+    pub fn dma_transfer(
+        mut self,
+        tx_buffer: &'static [u8],
+        rx_buffer: &'static mut [u8],
+    ) -> Result<Transfer<T>, (Error, Self, &'static [u8], &'static mut [u8])> {
+        if tx_buffer.len() > EASY_DMA_SIZE {
+            return Err((Error::TxBufferTooLong, self, tx_buffer, rx_buffer));
+        }
+        if rx_buffer.len() > EASY_DMA_SIZE {
+            return Err((Error::RxBufferTooLong, self, tx_buffer, rx_buffer));
+        }
+        if !slice_in_ram(tx_buffer) || !slice_in_ram(rx_buffer) {
+            return Err((Error::DMABufferNotInDataMemory, self, tx_buffer, rx_buffer));
+        }
+
+        self.start_spi_dma_transfer(
+            &DmaSlice::from_slice(tx_buffer),
+            &DmaSlice::from_slice(rx_buffer),
+        );
+
+        Ok(Transfer {
+            spim: self,
+            tx_buffer,
+            rx_buffer,
+        })
+    }
+
+    /// Start an EasyDMA ArrayList transfer
+    ///
+    /// In ArrayList mode, every START task transmits the next array of
+    /// `tx_buffer` while receiving into the next array of `rx_buffer`, after
+    /// which EasyDMA advances both pointers by `N` bytes, without any CPU
+    /// intervention. This allows e.g. a PPI channel to trigger START
+    /// periodically, see `ArrayList::tasks_start_address`. Either buffer may
+    /// be `None` if only one direction is needed. No transfer is started by
+    /// this method.
+    ///
+    /// The hardware doesn't report how far it has advanced, so PPI channel
+    /// `channel` is used to count the END events with `timer`, which is
+    /// switched to counter mode, see `ArrayList::completed`. On the nRF9160,
+    /// `channel` is a DPPI channel.
+    ///
+    /// # Safety
+    ///
+    /// The hardware does not know where the buffers end. The caller must make
+    /// sure that START is triggered no more than `M` times, otherwise EasyDMA
+    /// will access memory past their end.
+    pub unsafe fn array_list_transfer<C, const N: usize, const M: usize>(
+        self,
+        tx_buffer: Option<&'static [[u8; N]; M]>,
+        rx_buffer: Option<&'static mut [[u8; N]; M]>,
+        timer: C,
+        ppi: &Ppi,
+        channel: usize,
+    ) -> Result<ArrayList<T, C, N, M>, ArrayListError<T, C, N, M>>
+    where
+        C: timer::Instance,
+    {
+        if N == 0 || N > EASY_DMA_SIZE {
+            return Err((Error::ArrayListLength, self, tx_buffer, rx_buffer, timer));
+        }
+        if channel >= PPI_CHANNELS {
+            return Err((Error::InvalidPpiChannel, self, tx_buffer, rx_buffer, timer));
+        }
+        if !tx_buffer.map_or(true, |tx| list_in_ram(&tx[..]))
+            || !rx_buffer.as_ref().map_or(true, |rx| list_in_ram(&rx[..]))
+        {
+            return Err((Error::DMABufferNotInDataMemory, self, tx_buffer, rx_buffer, timer));
+        }
+
+        let tx = tx_buffer.map_or_else(DmaSlice::null, |tx| DmaSlice::from_array_list(&tx[..]));
+        let rx = rx_buffer
+            .as_ref()
+            .map_or_else(DmaSlice::null, |rx| DmaSlice::from_array_list(&rx[..]));
+
+        timer.tasks_stop.write(|w| unsafe { w.bits(1) });
+        timer.mode.write(|w| w.mode().counter());
+        timer.bitmode.write(|w| w.bitmode()._32bit());
+        timer.tasks_clear.write(|w| unsafe { w.bits(1) });
+        connect_end_to_count(
+            ppi,
+            channel,
+            &self.0.events_end as *const _ as u32,
+            &timer.tasks_count as *const _ as u32,
+        );
+        timer.tasks_start.write(|w| unsafe { w.bits(1) });
+
+        // Conservative compiler fence to prevent optimizations that do not
+        // take in to account actions by DMA. The fence has been placed here,
+        // before any DMA action has started
+        compiler_fence(SeqCst);
+
+        self.0.txd.list.write(|w| w.list().array_list());
+        self.0.rxd.list.write(|w| w.list().array_list());
+
+        self.0.txd.ptr.write(|w|
+            // The PTR field is a full 32 bits wide and accepts the full range
+            // of values. The buffer was checked to be in RAM above, and is
+            // owned by the returned `ArrayList` until it is stopped.
+            w.ptr().bits(tx.ptr));
+        self.0.txd.maxcnt.write(|w|
+            // `N` was checked against the EasyDMA size above, so it fits into
+            // the MAXCNT field.
+            w.maxcnt().bits(tx.len as _));
+        self.0.rxd.ptr.write(|w|
+            // This is safe for the same reasons that writing to TXD.PTR is
+            // safe. Please refer to the explanation there.
+            w.ptr().bits(rx.ptr));
+        self.0.rxd.maxcnt.write(|w|
+            // This is safe for the same reasons that writing to TXD.MAXCNT is
+            // safe. Please refer to the explanation there.
+            w.maxcnt().bits(rx.len as _));
+
+        Ok(ArrayList {
+            spim: self,
+            tx_buffer,
+            rx_buffer,
+            timer,
+            channel,
+        })
+    }
+
+    /// Enables the END interrupt for this SPIM
+    ///
+    /// The interrupt fires when a transfer started with `dma_transfer` has
+    /// completed. The END event stays set until `Transfer::wait` clears it,
+    /// so the interrupt handler must call `wait` once `Transfer::is_done`
+    /// returns `true`, or disable the interrupt. Otherwise the interrupt
+    /// fires again as soon as the handler returns.
+    ///
+    /// If access to the NVIC is not provided, the interrupt must ALSO be enabled
+    /// there outside of this function (e.g. manually call `nvic.enable`, or through
+    /// the use of RTFM).
+    ///
+    /// The blocking methods of `Spim` must not be used while the interrupt is
+    /// enabled, as a handler that clears the END event keeps them from
+    /// returning.
+    pub fn enable_interrupt(&mut self, nvic: Option<&mut NVIC>) {
+        self.0.intenset.write(|w| w.end().set());
+
+        if let Some(_nvic) = nvic {
+            unsafe { NVIC::unmask(T::INTERRUPT) };
+        }
+    }
+
+    /// Disables the END interrupt for this SPIM
+    ///
+    /// If access to the NVIC is not provided, the interrupt must ALSO be disabled
+    /// there outside of this function (e.g. manually call `nvic.disable`, or through
+    /// the use of RTFM).
+    pub fn disable_interrupt(&mut self, nvic: Option<&mut NVIC>) {
+        self.0.intenclr.write(|w| w.end().clear());
+
+        if let Some(_nvic) = nvic {
+            NVIC::mask(T::INTERRUPT);
+        }
+    }
+
     /// Read from an SPI slave
     ///
     /// This method is deprecated. Consider using `transfer` or `transfer_split`
+    ///
+    /// Sleeps until the END event, which the SPIM interrupt handler must not
+    /// clear while this blocks.
     #[inline(always)]
     pub fn read(
         &mut self,
@@ -243,6 +485,9 @@ where
     ///
     /// Uses the provided chip select pin to initiate the transaction. Transmits
     /// all bytes in `buffer`, then receives an equal number of bytes.
+    ///
+    /// Sleeps until the END event, which the SPIM interrupt handler must not
+    /// clear while this blocks.
     pub fn transfer(
         &mut self,
         chip_select: &mut Pin<Output<PushPull>>,
@@ -276,6 +521,9 @@ where
     ///
     /// If `tx_buffer.len() != rx_buffer.len()`, the transaction will stop at the
     /// smaller of either buffer.
+    ///
+    /// Sleeps until the END event, which the SPIM interrupt handler must not
+    /// clear while this blocks.
     pub fn transfer_split_even(
         &mut self,
         chip_select: &mut Pin<Output<PushPull>>,
@@ -313,6 +561,9 @@ where
     /// it is allowed to perform transactions where `tx_buffer.len() != rx_buffer.len()`.
     /// If this occurs, extra incoming bytes will be discarded, OR extra outgoing bytes
     /// will be filled with the `orc` value.
+    ///
+    /// Sleeps until the END event, which the SPIM interrupt handler must not
+    /// clear while this blocks.
     pub fn transfer_split_uneven(
         &mut self,
         chip_select: &mut Pin<Output<PushPull>>,
@@ -367,6 +618,9 @@ where
     /// This method uses the provided chip select pin to initiate the
     /// transaction, then transmits all bytes in `tx_buffer`. All incoming
     /// bytes are discarded.
+    ///
+    /// Sleeps until the END event, which the SPIM interrupt handler must not
+    /// clear while this blocks.
     pub fn write(
         &mut self,
         chip_select: &mut Pin<Output<PushPull>>,
@@ -382,6 +636,546 @@ where
     }
 }
 
+/// SPIM3 specific functionality
+///
+/// SPIM3 is the only instance on the nRF52840 that supports 16 and 32 MHz
+/// (see `HighSpeedFrequency`), a hardware controlled chip select (CSN) and a
+/// data/command (D/CX) line as used by many SPI displays. See nRF52840
+/// product specification, section 6.25.
+#[cfg(feature = "52840")]
+impl Spim<SPIM3> {
+    /// Hand the chip select pin over to the hardware
+    ///
+    /// The CSN line will be asserted automatically for the duration of each
+    /// transfer, with the given polarity. `csn_duration` is the minimum
+    /// number of 64 MHz clock cycles (15.625 ns) that CSN stays inactive
+    /// between two transfers. Use `clear_hw_csn` to get the pin back.
+    pub fn set_hw_csn(
+        &mut self,
+        csn: Pin<Output<PushPull>>,
+        polarity: CsnPolarity,
+        csn_duration: u8,
+    ) {
+        match polarity {
+            CsnPolarity::ActiveLow => self.0.csnpol.write(|w| w.csnpol().low()),
+            CsnPolarity::ActiveHigh => self.0.csnpol.write(|w| w.csnpol().high()),
+        }
+        self.0.iftiming.csndur.write(|w|
+            // The CSNDUR field is 8 bits wide and accepts the full range of
+            // values.
+            unsafe { w.csndur().bits(csn_duration) });
+
+        self.0.psel.csn.write(|w| {
+            let w = unsafe { w.pin().bits(csn.pin) };
+            let w = w.port().bit(csn.port);
+            w.connect().connected()
+        });
+    }
+
+    /// Take the chip select pin back from the hardware
+    ///
+    /// Disconnects CSN and returns the pin handed over through `set_hw_csn`,
+    /// or `None` if no pin is connected.
+    pub fn clear_hw_csn(&mut self) -> Option<Pin<Output<PushPull>>> {
+        let psel = self.0.psel.csn.read();
+        if psel.connect().is_disconnected() {
+            return None;
+        }
+        self.0.psel.csn.write(|w| w.connect().disconnected());
+
+        // Safe, as `set_hw_csn` consumed the pin when it was connected.
+        Some(unsafe { Pin::from_psel(psel.pin().bits(), psel.port().bit()) })
+    }
+
+    /// Set the number of 64 MHz clock cycles (15.625 ns) to delay the
+    /// sampling of MISO, to compensate for slow slaves at high frequencies.
+    ///
+    /// Only the lower 3 bits of `delay` are used.
+    pub fn set_rx_delay(&mut self, delay: u8) {
+        self.0.iftiming.rxdelay.write(|w|
+            // We mask the value to the 3 bits of the RXDELAY field.
+            unsafe { w.rxdelay().bits(delay & 0x07) });
+    }
+
+    /// Connect the D/CX (data/command) line
+    ///
+    /// Once connected, the line is driven low while command bytes are
+    /// transmitted and high for data bytes, see `write_command_data`.
+    pub fn set_dcx(&mut self, dcx: Pin<Output<PushPull>>) {
+        self.0.pseldcx.write(|w| {
+            let w = unsafe { w.pin().bits(dcx.pin) };
+            let w = w.port().bit(dcx.port);
+            w.connect().connected()
+        });
+    }
+
+    /// Disconnect the D/CX line and return the pin handed over through
+    /// `set_dcx`, or `None` if no pin is connected.
+    pub fn clear_dcx(&mut self) -> Option<Pin<Output<PushPull>>> {
+        let psel = self.0.pseldcx.read();
+        if psel.connect().is_disconnected() {
+            return None;
+        }
+        self.0.pseldcx.write(|w| w.connect().disconnected());
+
+        // Safe, as `set_dcx` consumed the pin when it was connected.
+        Some(unsafe { Pin::from_psel(psel.pin().bits(), psel.port().bit()) })
+    }
+
+    /// Write a command, followed by its data, to an SPI display
+    ///
+    /// The D/CX line configured through `set_dcx` is held low while
+    /// `command` is transmitted, then high while `data` is transmitted. All
+    /// incoming bytes are discarded.
+    ///
+    /// This method doesn't take a chip select pin. Use `set_hw_csn` to let
+    /// the hardware drive CSN, or assert chip select before calling it.
+    ///
+    /// Sleeps until the END event, which the SPIM interrupt handler must not
+    /// clear while this blocks.
+    pub fn write_command_data(&mut self, command: &[u8], data: &[u8]) -> Result<(), Error> {
+        ram_slice_check(command)?;
+        ram_slice_check(data)?;
+
+        // A DCXCNT value of 0xF marks every byte of the transfer as a command
+        // byte, so the command can be of any length.
+        self.0.dcxcnt.write(|w| unsafe { w.dcxcnt().bits(0xF) });
+        let res = command.chunks(EASY_DMA_SIZE).try_for_each(|chunk| {
+            self.do_spi_dma_transfer(DmaSlice::from_slice(chunk), DmaSlice::null())
+        });
+
+        // Don't return early, as we must switch back to data mode
+        self.0.dcxcnt.write(|w| unsafe { w.dcxcnt().bits(0) });
+        res?;
+
+        data.chunks(EASY_DMA_SIZE).try_for_each(|chunk| {
+            self.do_spi_dma_transfer(DmaSlice::from_slice(chunk), DmaSlice::null())
+        })
+    }
+}
+
+/// Polarity of the hardware controlled chip select line of SPIM3
+#[cfg(feature = "52840")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CsnPolarity {
+    ActiveLow,
+    ActiveHigh,
+}
+
+/// Order in which the bits of each byte are transmitted
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BitOrder {
+    /// Most significant bit first
+    MsbFirst,
+    /// Least significant bit first
+    LsbFirst,
+}
+
+/// SPI clock frequency, supported by all SPIM instances
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u32)]
+pub enum Frequency {
+    /// 125 kbps
+    K125 = 0x0200_0000,
+    /// 250 kbps
+    K250 = 0x0400_0000,
+    /// 500 kbps
+    K500 = 0x0800_0000,
+    /// 1 Mbps
+    M1 = 0x1000_0000,
+    /// 2 Mbps
+    M2 = 0x2000_0000,
+    /// 4 Mbps
+    M4 = 0x4000_0000,
+    /// 8 Mbps
+    M8 = 0x8000_0000,
+}
+
+/// SPI clock frequencies only supported by SPIM3 on the nRF52840
+#[cfg(feature = "52840")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u32)]
+pub enum HighSpeedFrequency {
+    /// 16 Mbps
+    M16 = 0x0A00_0000,
+    /// 32 Mbps
+    M32 = 0x1400_0000,
+}
+
+/// A SPI clock frequency that SPIM instance `T` supports
+///
+/// Lets `Spim::new` and `Spim::set_frequency` reject a `HighSpeedFrequency`
+/// at compile time for instances other than SPIM3.
+pub trait FrequencyFor<T>: Copy {
+    /// The value of the FREQUENCY register
+    fn bits(self) -> u32;
+}
+
+impl<T> FrequencyFor<T> for Frequency
+where
+    T: Instance,
+{
+    fn bits(self) -> u32 {
+        self as u32
+    }
+}
+
+#[cfg(feature = "52840")]
+impl<T> FrequencyFor<T> for HighSpeedFrequency
+where
+    T: HighSpeedInstance,
+{
+    fn bits(self) -> u32 {
+        self as u32
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T> Spim<T>
+where
+    T: Instance,
+{
+    /// Asynchronous version of `transfer`
+    ///
+    /// Requires `on_interrupt` to be called from the SPIM interrupt handler.
+    /// Dropping the returned future stops the transfer and resets the CS pin.
+    pub async fn transfer_async(
+        &mut self,
+        chip_select: &mut Pin<Output<PushPull>>,
+        buffer: &mut [u8],
+    ) -> Result<(), Error> {
+        ram_slice_check(buffer)?;
+
+        chip_select.set_low().unwrap();
+        let cs = OnDrop::new(|| chip_select.set_high().unwrap());
+
+        for chunk in buffer.chunks(EASY_DMA_SIZE) {
+            self.do_spi_dma_transfer_async(
+                DmaSlice::from_slice(chunk),
+                DmaSlice::from_slice(chunk),
+            )
+            .await?;
+        }
+
+        // Resets the CS pin
+        drop(cs);
+
+        Ok(())
+    }
+
+    /// Internal helper function to execute a SPIM DMA transfer without
+    /// blocking
+    async fn do_spi_dma_transfer_async(
+        &mut self,
+        tx: DmaSlice,
+        rx: DmaSlice,
+    ) -> Result<(), Error> {
+        self.start_spi_dma_transfer(&tx, &rx);
+
+        let spim = &*self.0;
+        let on_drop = OnDrop::new(|| {
+            // The future was dropped before the transfer completed, so stop
+            // it before the buffers go away.
+            spim.intenclr.write(|w| w.end().clear());
+            spim.tasks_stop.write(|w| unsafe { w.bits(1) });
+
+            // STOPPED follows once the byte in progress has been clocked
+            // out, which the drop can only wait for by spinning.
+            while spim.events_stopped.read().bits() == 0 {}
+            spim.events_stopped.write(|w| w);
+            spim.events_end.write(|w| w);
+        });
+
+        poll_fn(|cx| {
+            T::waker().register(cx.waker());
+            spim.intenset.write(|w| w.end().set());
+
+            if spim.events_end.read().bits() != 0 {
+                Poll::Ready(())
+            } else {
+                Poll::Pending
+            }
+        })
+        .await;
+        on_drop.defuse();
+
+        self.finish_spi_dma_transfer(&tx, &rx)
+    }
+}
+
+/// Interrupt handler for the `async` methods of `Spim<T>`
+///
+/// Must be called from the interrupt handler of the SPIM instance `T`.
+#[cfg(feature = "async")]
+pub fn on_interrupt<T: Instance>() {
+    let spim = T::regs();
+
+    if spim.events_end.read().bits() != 0 {
+        // The event itself is reset by the woken task
+        spim.intenclr.write(|w| w.end().clear());
+        T::waker().wake();
+    }
+}
+
+/// A non-blocking SPIM transfer, started by `Spim::dma_transfer`
+///
+/// The transfer owns the SPIM and both buffers while EasyDMA is accessing
+/// them.
+pub struct Transfer<T> {
+    spim: Spim<T>,
+    tx_buffer: &'static [u8],
+    rx_buffer: &'static mut [u8],
+}
+
+impl<T> Transfer<T>
+where
+    T: Instance,
+{
+    /// Check whether the transfer has completed
+    ///
+    /// This does not clear the END event, so it can be called from the SPIM
+    /// interrupt before calling `wait`. If it returns `true` in the
+    /// interrupt handler, call `wait` before returning, as the END interrupt
+    /// stays pending until the event is cleared.
+    pub fn is_done(&self) -> bool {
+        self.spim.0.events_end.read().bits() != 0
+    }
+
+    /// Block until the transfer has completed
+    ///
+    /// Returns the result of the transfer together with the SPIM and the
+    /// buffers. Returns immediately if `is_done` already returned `true`,
+    /// which makes this the method to call from the SPIM interrupt.
+    ///
+    /// Sleeps until the END event. An interrupt handler other than the one
+    /// calling this must not clear it in the meantime.
+    pub fn wait(mut self) -> (Result<(), Error>, Spim<T>, &'static [u8], &'static mut [u8]) {
+        let spim = &self.spim.0;
+        wait_for_event(spim, T::INTERRUPT, 1 << 6, || {
+            spim.events_end.read().bits() != 0
+        });
+
+        let res = self.spim.finish_spi_dma_transfer(
+            &DmaSlice::from_slice(self.tx_buffer),
+            &DmaSlice::from_slice(self.rx_buffer),
+        );
+
+        (res, self.spim, self.tx_buffer, self.rx_buffer)
+    }
+}
+
+/// A SPIM running in EasyDMA ArrayList mode, see `Spim::array_list_transfer`
+pub struct ArrayList<T, C, const N: usize, const M: usize>
+where
+    T: Instance,
+    C: timer::Instance,
+{
+    spim: Spim<T>,
+    tx_buffer: Option<&'static [[u8; N]; M]>,
+    rx_buffer: Option<&'static mut [[u8; N]; M]>,
+    timer: C,
+    channel: usize,
+}
+
+/// Returned by `Spim::array_list_transfer` if the transfer can't be set up,
+/// together with the resources that were passed in
+pub type ArrayListError<T, C, const N: usize, const M: usize> = (
+    Error,
+    Spim<T>,
+    Option<&'static [[u8; N]; M]>,
+    Option<&'static mut [[u8; N]; M]>,
+    C,
+);
+
+impl<T, C, const N: usize, const M: usize> ArrayList<T, C, N, M>
+where
+    T: Instance,
+    C: timer::Instance,
+{
+    /// Start the transfer of the next arrays from software
+    pub fn start(&mut self) {
+        self.spim.0.tasks_start.write(|w|
+            // `1` is a valid value to write to task registers.
+            unsafe { w.bits(1) });
+    }
+
+    /// Return the address of the START task, to be used as a PPI task
+    /// endpoint
+    pub fn tasks_start_address(&self) -> u32 {
+        &self.spim.0.tasks_start as *const _ as u32
+    }
+
+    /// Return the address of the END event, to be used as a PPI event
+    /// endpoint
+    pub fn events_end_address(&self) -> u32 {
+        &self.spim.0.events_end as *const _ as u32
+    }
+
+    /// Return the number of transfers that have completed so far
+    ///
+    /// Read from the TIMER that counts the END events, by capturing to CC[1].
+    pub fn completed(&self) -> usize {
+        self.timer.tasks_capture[1].write(|w| unsafe { w.bits(1) });
+        self.timer.cc[1].read().bits() as usize
+    }
+
+    /// Return the number of arrays in the buffers
+    pub fn capacity(&self) -> usize {
+        M
+    }
+
+    /// Stop the SPIM and leave ArrayList mode
+    ///
+    /// Returns the SPIM, the buffers and the TIMER, and disconnects the PPI
+    /// channel. A transfer in progress is aborted.
+    ///
+    /// The SPIM interrupt handler must not clear the STOPPED event while
+    /// this waits for it. The same applies to dropping the `ArrayList`.
+    pub fn stop(
+        self,
+    ) -> (
+        Spim<T>,
+        Option<&'static [[u8; N]; M]>,
+        Option<&'static mut [[u8; N]; M]>,
+        C,
+    ) {
+        let mut this = ManuallyDrop::new(self);
+        this.halt();
+
+        // Safe, as `this` is not dropped, so every field is moved out exactly
+        // once.
+        unsafe {
+            (
+                ptr::read(&this.spim),
+                ptr::read(&this.tx_buffer),
+                ptr::read(&this.rx_buffer),
+                ptr::read(&this.timer),
+            )
+        }
+    }
+
+    /// Abort the transfer in progress, leave ArrayList mode and disconnect
+    /// the PPI channel
+    fn halt(&mut self) {
+        let spim = &self.spim.0;
+
+        spim.tasks_stop.write(|w|
+            // `1` is a valid value to write to task registers.
+            unsafe { w.bits(1) });
+        // STOPPED follows once the byte in progress has been clocked out
+        wait_for_event(spim, T::INTERRUPT, 1 << 1, || {
+            spim.events_stopped.read().bits() != 0
+        });
+        spim.events_stopped.write(|w| w);
+        spim.events_end.write(|w| w);
+
+        // Conservative compiler fence to prevent optimizations that do not
+        // take in to account actions by DMA. The fence has been placed here,
+        // after all possible DMA actions have completed
+        compiler_fence(SeqCst);
+
+        spim.txd.list.write(|w| w.list().disabled());
+        spim.rxd.list.write(|w| w.list().disabled());
+
+        disconnect_end_from_count(
+            self.channel,
+            &spim.events_end as *const _ as u32,
+            &self.timer.tasks_count as *const _ as u32,
+        );
+        self.timer.tasks_stop.write(|w| unsafe { w.bits(1) });
+    }
+}
+
+impl<T, C, const N: usize, const M: usize> Drop for ArrayList<T, C, N, M>
+where
+    T: Instance,
+    C: timer::Instance,
+{
+    /// Stops the transfer before EasyDMA loses access to the buffers, see
+    /// `ArrayList::stop`
+    fn drop(&mut self) {
+        self.halt();
+    }
+}
+
+/// The peripheral that connects events to tasks
+#[cfg(not(feature = "9160"))]
+pub type Ppi = PPI;
+/// The peripheral that connects events to tasks
+#[cfg(feature = "9160")]
+pub type Ppi = DPPIC;
+
+/// Number of programmable (D)PPI channels
+#[cfg(not(feature = "9160"))]
+const PPI_CHANNELS: usize = 20;
+#[cfg(feature = "9160")]
+const PPI_CHANNELS: usize = 16;
+
+/// Offset of an event's PUBLISH or a task's SUBSCRIBE register from the
+/// event or task register
+#[cfg(feature = "9160")]
+const DPPI_CONFIG_OFFSET: u32 = 0x80;
+
+/// DPPI PUBLISH/SUBSCRIBE: enable the connection to the channel
+#[cfg(feature = "9160")]
+const DPPI_ENABLE: u32 = 1 << 31;
+
+/// Trigger the task at address `task` whenever the event at `event` occurs
+#[cfg(not(feature = "9160"))]
+fn connect_end_to_count(ppi: &Ppi, channel: usize, event: u32, task: u32) {
+    ppi.ch[channel].eep.write(|w|
+        // Any address is a valid event endpoint, `event` points to the END
+        // event of a SPIM.
+        unsafe { w.bits(event) });
+    ppi.ch[channel].tep.write(|w|
+        // Any address is a valid task endpoint, `task` points to the COUNT
+        // task of a TIMER.
+        unsafe { w.bits(task) });
+    ppi.chenset.write(|w|
+        // `channel` was checked against the number of channels.
+        unsafe { w.bits(1 << channel) });
+}
+
+/// Trigger the task at address `task` whenever the event at `event` occurs
+#[cfg(feature = "9160")]
+fn connect_end_to_count(dppi: &Ppi, channel: usize, event: u32, task: u32) {
+    let config = channel as u32 | DPPI_ENABLE;
+
+    // The PUBLISH and SUBSCRIBE registers are not part of the PAC's shared
+    // register blocks. Both accept a channel number below 16 and the enable
+    // bit, and `channel` was checked against the number of channels.
+    unsafe {
+        ((event + DPPI_CONFIG_OFFSET) as *mut u32).write_volatile(config);
+        ((task + DPPI_CONFIG_OFFSET) as *mut u32).write_volatile(config);
+    }
+    dppi.chenset.write(|w|
+        // See above
+        unsafe { w.bits(1 << channel) });
+}
+
+/// Undo `connect_end_to_count`
+#[cfg(not(feature = "9160"))]
+fn disconnect_end_from_count(channel: usize, _event: u32, _task: u32) {
+    // The channel was claimed by `array_list_transfer`, so nothing else
+    // accesses it.
+    let ppi = unsafe { &*PPI::ptr() };
+    ppi.chenclr.write(|w|
+        // `channel` was checked against the number of channels.
+        unsafe { w.bits(1 << channel) });
+}
+
+/// Undo `connect_end_to_count`
+#[cfg(feature = "9160")]
+fn disconnect_end_from_count(channel: usize, event: u32, task: u32) {
+    // The channel was claimed by `array_list_transfer`, so nothing else
+    // accesses it. Writing 0 disconnects the event and the task.
+    unsafe {
+        ((event + DPPI_CONFIG_OFFSET) as *mut u32).write_volatile(0);
+        ((task + DPPI_CONFIG_OFFSET) as *mut u32).write_volatile(0);
+        (*DPPIC::ptr()).chenclr.write(|w| w.bits(1 << channel));
+    }
+}
+
 /// GPIO pins for SPIM interface
 pub struct Pins {
     /// SPI clock
@@ -404,6 +1198,11 @@ pub enum Error {
     DMABufferNotInDataMemory,
     Transmit,
     Receive,
+    /// The length of the ArrayList arrays is zero, or not supported by
+    /// EasyDMA
+    ArrayListLength,
+    /// The (D)PPI channel doesn't exist
+    InvalidPpiChannel,
 }
 
 fn ram_slice_check(slice: &[u8]) -> Result<(), Error> {
@@ -416,12 +1215,70 @@ fn ram_slice_check(slice: &[u8]) -> Result<(), Error> {
 
 
 /// Implemented by all SPIM instances
-pub trait Instance: Deref<Target = spim0::RegisterBlock> {}
+pub trait Instance: Deref<Target = spim0::RegisterBlock> {
+    /// The interrupt associated with this SPIM instance
+    const INTERRUPT: Interrupt;
+
+    /// The waker of the task waiting on this instance
+    #[cfg(feature = "async")]
+    fn waker() -> &'static crate::waker::WakerRegistration;
 
-impl Instance for SPIM0 {}
+    /// The register block of this instance, used by `on_interrupt`
+    #[cfg(feature = "async")]
+    fn regs() -> &'static spim0::RegisterBlock;
+}
+
+#[cfg(feature = "52810")]
+impl Instance for SPIM0 {
+    const INTERRUPT: Interrupt = Interrupt::SPIM0_SPIS0_SPI0;
+
+    #[cfg(feature = "async")]
+    impl_async_instance!(SPIM0, spim0::RegisterBlock);
+}
 
 #[cfg(any(feature = "52832", feature = "52840"))]
-impl Instance for SPIM1 {}
+impl Instance for SPIM0 {
+    const INTERRUPT: Interrupt = Interrupt::SPIM0_SPIS0_TWIM0_TWIS0_SPI0_TWI0;
+
+    #[cfg(feature = "async")]
+    impl_async_instance!(SPIM0, spim0::RegisterBlock);
+}
+
+#[cfg(feature = "9160")]
+impl Instance for SPIM0 {
+    const INTERRUPT: Interrupt = Interrupt::UARTE0_SPIM0_SPIS0_TWIM0_TWIS0;
+
+    #[cfg(feature = "async")]
+    impl_async_instance!(SPIM0, spim0::RegisterBlock);
+}
+
+#[cfg(any(feature = "52832", feature = "52840"))]
+impl Instance for SPIM1 {
+    const INTERRUPT: Interrupt = Interrupt::SPIM1_SPIS1_TWIM1_TWIS1_SPI1_TWI1;
+
+    #[cfg(feature = "async")]
+    impl_async_instance!(SPIM1, spim0::RegisterBlock);
+}
 
 #[cfg(any(feature = "52832", feature = "52840"))]
-impl Instance for SPIM2 {}
+impl Instance for SPIM2 {
+    const INTERRUPT: Interrupt = Interrupt::SPIM2_SPIS2_SPI2;
+
+    #[cfg(feature = "async")]
+    impl_async_instance!(SPIM2, spim0::RegisterBlock);
+}
+
+#[cfg(feature = "52840")]
+impl Instance for SPIM3 {
+    const INTERRUPT: Interrupt = Interrupt::SPIM3;
+
+    #[cfg(feature = "async")]
+    impl_async_instance!(SPIM3, spim0::RegisterBlock);
+}
+
+/// Implemented by the SPIM instances that support a `HighSpeedFrequency`
+#[cfg(feature = "52840")]
+pub trait HighSpeedInstance: Instance {}
+
+#[cfg(feature = "52840")]
+impl HighSpeedInstance for SPIM3 {}
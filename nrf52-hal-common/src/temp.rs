@@ -1,9 +1,13 @@
 //! Temperature sensor interface.
+//!
+//! Not available on the nRF9160, which has no TEMP peripheral accessible to
+//! the application.
 
 use fpa::I30F2;
 use nb;
 use void::Void;
-use crate::target::TEMP;
+use crate::target::{Interrupt, NVIC, TEMP};
+use crate::wait::wait_for_event;
 
 /// Integrated temperature sensor.
 pub struct Temp(TEMP);
@@ -14,14 +18,36 @@ impl Temp {
         Temp(raw)
     }
 
+    /// Return the raw interface to the underlying TEMP peripheral
+    pub fn free(self) -> TEMP {
+        self.0
+    }
+
     /// Starts a new measurement and blocks until completion.
     ///
     /// If a measurement was already started, it will be canceled.
+    ///
+    /// The TEMP interrupt handler must not clear the DATARDY event while this
+    /// blocks, or it never returns.
     pub fn measure(&mut self) -> I30F2 {
+        I30F2::from_bits(self.measure_raw())
+    }
+
+    /// Like `measure`, but returns the temperature in milli-degrees Celsius.
+    pub fn measure_millicelsius(&mut self) -> i32 {
+        raw_to_millicelsius(self.measure_raw())
+    }
+
+    fn measure_raw(&mut self) -> i32 {
         self.stop_measurement();
         self.start_measurement();
 
-        nb::block!(self.read()).unwrap()
+        let temp = &self.0;
+        wait_for_event(temp, Interrupt::TEMP, 1 << 0, || {
+            temp.events_datardy.read().bits() != 0
+        });
+
+        nb::block!(self.read_raw()).unwrap()
     }
 
     /// Kicks off a temperature measurement.
@@ -47,12 +73,59 @@ impl Temp {
     ///
     /// Returns the measured temperature in °C.
     pub fn read(&mut self) -> nb::Result<I30F2, Void> {
+        self.read_raw().map(I30F2::from_bits)
+    }
+
+    /// Like `read`, but returns the temperature in milli-degrees Celsius.
+    pub fn read_millicelsius(&mut self) -> nb::Result<i32, Void> {
+        self.read_raw().map(raw_to_millicelsius)
+    }
+
+    fn read_raw(&mut self) -> nb::Result<i32, Void> {
         if self.0.events_datardy.read().bits() == 0 {
-            return Err(nb::Error::WouldBlock);
+            Err(nb::Error::WouldBlock)
         } else {
             self.0.events_datardy.reset(); // clear event
-            let raw = self.0.temp.read().bits();
-            Ok(I30F2::from_bits(raw as i32))
+            Ok(self.0.temp.read().bits() as i32)
+        }
+    }
+
+    /// Enables the interrupt that fires when a measurement is ready.
+    ///
+    /// Start the measurement with `start_measurement`, then fetch it with
+    /// `read` or `read_millicelsius` from the TEMP interrupt handler, which
+    /// also clears the event.
+    ///
+    /// If access to the NVIC is not provided, the interrupt must ALSO be enabled
+    /// there outside of this function (e.g. manually call `nvic.enable`, or through
+    /// the use of RTFM).
+    ///
+    /// While it is enabled, don't use `measure` or `measure_millicelsius`, as
+    /// reading the measurement from the handler clears the event they wait
+    /// for.
+    pub fn enable_interrupt(&mut self, nvic: Option<&mut NVIC>) {
+        self.0.intenset.write(|w| w.datardy().set());
+
+        if let Some(_nvic) = nvic {
+            unsafe { NVIC::unmask(Interrupt::TEMP) };
         }
     }
+
+    /// Disables the interrupt that fires when a measurement is ready.
+    ///
+    /// If access to the NVIC is not provided, the interrupt must ALSO be disabled
+    /// there outside of this function (e.g. manually call `nvic.disable`, or through
+    /// the use of RTFM).
+    pub fn disable_interrupt(&mut self, nvic: Option<&mut NVIC>) {
+        self.0.intenclr.write(|w| w.datardy().clear());
+
+        if let Some(_nvic) = nvic {
+            NVIC::mask(Interrupt::TEMP);
+        }
+    }
+}
+
+/// Converts a raw TEMP reading, in steps of 0.25 °C, to milli-degrees Celsius.
+fn raw_to_millicelsius(raw: i32) -> i32 {
+    raw * 250
 }
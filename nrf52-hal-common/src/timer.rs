@@ -2,7 +2,10 @@
 //!
 //! See product specification, chapter 24.
 
-use core::ops::Deref;
+use core::convert::TryFrom;
+use core::num::TryFromIntError;
+use core::ops::{Add, AddAssign, Deref, Sub, SubAssign};
+use core::sync::atomic::{compiler_fence, AtomicU32, Ordering};
 
 #[cfg(feature="9160")]
 use crate::target::{timer0_ns as timer0, Interrupt, NVIC, TIMER0_NS as TIMER0, TIMER1_NS as TIMER1, TIMER2_NS as TIMER2};
@@ -10,26 +13,176 @@ use crate::target::{timer0_ns as timer0, Interrupt, NVIC, TIMER0_NS as TIMER0, T
 #[cfg(not(feature="9160"))]
 use crate::target::{timer0, Interrupt, NVIC, TIMER0, TIMER1, TIMER2};
 
-use embedded_hal::{prelude::*, timer};
+use embedded_hal::{
+    blocking::delay::{DelayMs, DelayUs},
+    prelude::*,
+    timer,
+};
 use nb::{self, block};
 use void::{unreachable, Void};
 
 #[cfg(any(feature = "52832", feature = "52840"))]
 use crate::target::{TIMER3, TIMER4};
 
+use crate::time::{Hertz, MicroSeconds, MilliSeconds, Seconds};
+use crate::wait::{sleep_until, wait_for_event, with_event_interrupts};
+
 use core::marker::PhantomData;
 
+/// Frequency of the clock the prescaler divides down
+const BASE_FREQUENCY: u32 = 16_000_000;
+
+/// Highest prescaler value supported by the hardware (31.25 kHz)
+const MAX_PRESCALER: u8 = 9;
+
 pub struct OneShot;
 pub struct Periodic;
 
 
+/// Width of the timer's counter
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BitMode {
+    Bits8,
+    Bits16,
+    Bits24,
+    Bits32,
+}
+
+impl BitMode {
+    /// The largest value the counter can reach
+    pub fn max_value(self) -> u32 {
+        match self {
+            BitMode::Bits8 => 0xFF,
+            BitMode::Bits16 => 0xFFFF,
+            BitMode::Bits24 => 0xFF_FFFF,
+            BitMode::Bits32 => 0xFFFF_FFFF,
+        }
+    }
+}
+
+
+/// Configures a TIMER instance
+///
+/// Defaults to a frequency of 1 MHz and 32 bits accuracy, which is what
+/// `Timer::new` uses.
+pub struct Builder<T> {
+    timer: T,
+    prescaler: u8,
+    bit_mode: BitMode,
+}
+
+impl<T> Builder<T>
+where
+    T: Instance,
+{
+    pub fn new(timer: T) -> Self {
+        Builder {
+            timer,
+            prescaler: 4, // 1 MHz
+            bit_mode: BitMode::Bits32,
+        }
+    }
+
+    /// Set the prescaler directly
+    ///
+    /// The timer runs at 16 MHz / 2^`prescaler`. Values above 9 are treated
+    /// as 9 (31.25 kHz), as the hardware would.
+    pub fn prescaler(mut self, prescaler: u8) -> Self {
+        self.prescaler = if prescaler > MAX_PRESCALER {
+            MAX_PRESCALER
+        } else {
+            prescaler
+        };
+        self
+    }
+
+    /// Select the prescaler from a target frequency
+    ///
+    /// Picks the highest available frequency that doesn't exceed `frequency`,
+    /// or 31.25 kHz if `frequency` is lower than that. Use `Timer::frequency`
+    /// to find out which frequency was selected.
+    pub fn frequency<F>(mut self, frequency: F) -> Self
+    where
+        F: Into<Hertz>,
+    {
+        let Hertz(frequency) = frequency.into();
+
+        self.prescaler = (0..MAX_PRESCALER)
+            .find(|&prescaler| BASE_FREQUENCY >> prescaler <= frequency)
+            .unwrap_or(MAX_PRESCALER);
+        self
+    }
+
+    /// Set the width of the counter
+    pub fn bit_mode(mut self, bit_mode: BitMode) -> Self {
+        self.bit_mode = bit_mode;
+        self
+    }
+
+    /// Finish as a timer that stops after each period
+    pub fn one_shot(self) -> Timer<T, OneShot> {
+        self.configure_timer();
+        self.timer
+            .shorts
+            .write(|w| w.compare0_clear().enabled().compare0_stop().enabled());
+
+        Timer::<T, OneShot>(self.timer, PhantomData)
+    }
+
+    /// Finish as a timer that restarts after each period
+    pub fn periodic(self) -> Timer<T, Periodic> {
+        self.configure_timer();
+        self.timer
+            .shorts
+            .write(|w| w.compare0_clear().enabled().compare0_stop().disabled());
+
+        Timer::<T, Periodic>(self.timer, PhantomData)
+    }
+
+    /// Finish as a counter of external events
+    ///
+    /// The prescaler is not used in counter mode.
+    pub fn counter(self) -> Counter<T> {
+        self.timer.tasks_stop.write(|w| unsafe { w.bits(1) });
+        self.timer.shorts.reset();
+        self.timer.mode.write(|w| w.mode().counter());
+        self.write_bit_mode();
+
+        Counter(self.timer)
+    }
+
+    fn configure_timer(&self) {
+        self.timer.tasks_stop.write(|w| unsafe { w.bits(1) });
+        self.timer.mode.write(|w| w.mode().timer());
+        self.timer
+            .prescaler
+            .write(|w| unsafe { w.prescaler().bits(self.prescaler) });
+        self.write_bit_mode();
+    }
+
+    fn write_bit_mode(&self) {
+        self.timer.bitmode.write(|w| {
+            let w = w.bitmode();
+            match self.bit_mode {
+                BitMode::Bits8 => w._08bit(),
+                BitMode::Bits16 => w._16bit(),
+                BitMode::Bits24 => w._24bit(),
+                BitMode::Bits32 => w._32bit(),
+            }
+        });
+    }
+}
+
+
 /// Interface to a TIMER instance
 ///
-/// Right now, this is a very basic interface. The timer will always be
-/// hardcoded to a frequency of 1 MHz and 32 bits accuracy.
+/// `Timer::new` configures the timer for a frequency of 1 MHz and 32 bits
+/// accuracy. Use `Timer::builder` to select a different frequency or bit
+/// width, or to use the TIMER as a `Counter`.
 ///
 /// CC[0] is used for the current/most-recent delay period and CC[1] is used
-/// to grab the current value of the counter at a given instant.
+/// to grab the current value of the counter at a given instant. The remaining
+/// channels are available through `cc2` to `cc5`.
 pub struct Timer<T, U = OneShot>(T, PhantomData<U>);
 
 impl<T> Timer<T, OneShot>
@@ -37,21 +190,18 @@ where
     T: Instance,
 {
     pub fn one_shot(timer: T) -> Timer<T, OneShot> {
-        timer
-            .shorts
-            .write(|w| w.compare0_clear().enabled().compare0_stop().enabled());
-        timer.prescaler.write(
-            |w| unsafe { w.prescaler().bits(4) }, // 1 MHz
-        );
-        timer.bitmode.write(|w| w.bitmode()._32bit());
-
-        Timer::<T, OneShot>(timer, PhantomData)
+        Builder::new(timer).one_shot()
     }
 
     pub fn new(timer: T) -> Timer<T, OneShot> {
         Timer::<T, OneShot>::one_shot(timer)
     }
 
+    /// Configure the timer's frequency, bit width or mode
+    pub fn builder(timer: T) -> Builder<T> {
+        Builder::new(timer)
+    }
+
 }
 
 impl<T> Timer<T, Periodic>
@@ -59,15 +209,7 @@ where
     T: Instance,
 {
     pub fn periodic(timer: T) -> Timer<T, Periodic> {
-        timer
-            .shorts
-            .write(|w| w.compare0_clear().enabled().compare0_stop().disabled());
-        timer.prescaler.write(
-            |w| unsafe { w.prescaler().bits(4) }, // 1 MHz
-        );
-        timer.bitmode.write(|w| w.bitmode()._32bit());
-
-        Timer::<T, Periodic>(timer, PhantomData)
+        Builder::new(timer).periodic()
     }
 
 }
@@ -76,6 +218,28 @@ impl<T, U> Timer<T, U>
 where
     T: Instance,
 {
+    /// The frequency the timer runs at
+    pub fn frequency(&self) -> Hertz {
+        let prescaler = self.0.prescaler.read().prescaler().bits();
+        if prescaler > MAX_PRESCALER {
+            Hertz(BASE_FREQUENCY >> MAX_PRESCALER)
+        } else {
+            Hertz(BASE_FREQUENCY >> prescaler)
+        }
+    }
+
+    /// The width of the timer's counter
+    pub fn bit_mode(&self) -> BitMode {
+        match self.0.bitmode.read().bitmode().bits() {
+            0 => BitMode::Bits16,
+            1 => BitMode::Bits8,
+            2 => BitMode::Bits24,
+            _ => BitMode::Bits32,
+        }
+    }
+
+    /// Ticks per second of a timer created through `Timer::new`
+    #[deprecated(note = "the frequency is configurable, use `Timer::frequency`")]
     pub const TICKS_PER_SECOND: u32 = 1_000_000;
 
     pub fn into_periodic(self) -> Timer<T, Periodic> {
@@ -105,14 +269,43 @@ where
         self.0.cc[1].read().bits()
     }
 
+    /// Capture/compare channel 2
+    pub fn cc2(&mut self) -> CcChannel<T> {
+        CcChannel::new(&self.0, 2)
+    }
+
+    /// Capture/compare channel 3
+    pub fn cc3(&mut self) -> CcChannel<T> {
+        CcChannel::new(&self.0, 3)
+    }
+
+    /// Capture/compare channel 4
+    pub fn cc4(&mut self) -> CcChannel<T>
+    where
+        T: SixChannelInstance,
+    {
+        CcChannel::new(&self.0, 4)
+    }
+
+    /// Capture/compare channel 5
+    pub fn cc5(&mut self) -> CcChannel<T>
+    where
+        T: SixChannelInstance,
+    {
+        CcChannel::new(&self.0, 5)
+    }
+
     /// Enables the interrupt for this timer
     ///
-    /// Enables an interrupt that is fired when the timer reaches the value that
-    /// is given as an argument to `start`.
+    /// Enables an interrupt that is fired on COMPARE[0], i.e. when the duration
+    /// given to `start`, or the number of cycles given to `start_cycles`, has
+    /// elapsed at the configured frequency.
     ///
     /// If access to the NVIC is not provided, the interrupt must ALSO be enabled
     /// there outside of this function (e.g. manually call `nvic.enable`, or through
     /// the use of RTFM).
+    ///
+    /// The handler must not clear the COMPARE[0] event while `delay` blocks.
     pub fn enable_interrupt(&mut self, nvic: Option<&mut NVIC>) {
         // As of this writing, the timer code only uses
         // `cc[0]`/`events_compare[0]`. If the code is extended to use other
@@ -126,8 +319,7 @@ where
 
     /// Disables the interrupt for this timer
     ///
-    /// Disables an interrupt that is fired when the timer reaches the value
-    /// that is given as an argument to `start`.
+    /// Disables the COMPARE[0] interrupt enabled by `enable_interrupt`.
     ///
     /// If access to the NVIC is not provided, the interrupt must ALSO be disabled
     /// there outside of this function (e.g. manually call `nvic.disable`, or through
@@ -143,29 +335,60 @@ where
         }
     }
 
+    /// Block for the given number of timer cycles
+    ///
+    /// If the interrupt is enabled, its handler must not clear the COMPARE[0]
+    /// event while this blocks, or it never returns. The same applies to the
+    /// `DelayMs` and `DelayUs` implementations.
     pub fn delay(&mut self, cycles: u32) {
-        self.start(cycles);
+        self.start_cycles(cycles);
+
+        let timer = &self.0;
+        wait_for_event(timer, T::INTERRUPT, 1 << 16, || {
+            timer.events_compare[0].read().bits() != 0
+        });
+
         match block!(self.wait()) {
             Ok(_) => {}
             Err(x) => unreachable(x),
         }
     }
-}
 
-impl<T, U> timer::CountDown for Timer<T, U>
-where
-    T: Instance,
-{
-    type Time = u32;
+    /// Sleep until the COMPARE[0] event occurs or `done` returns `true`
+    ///
+    /// `done` must check an event whose interrupt is enabled as well, see
+    /// `wait::with_event_interrupts`. The event is left for `wait` to clear.
+    pub(crate) fn sleep_until_compare_or(&self, mut done: impl FnMut() -> bool) {
+        let timer = &self.0;
+        with_event_interrupts(timer, T::INTERRUPT, 1 << 16, || {
+            sleep_until(|| timer.events_compare[0].read().bits() != 0 || done())
+        });
+    }
 
-    /// Start the timer
+    /// Block for a number of cycles that may exceed the configured bit width
+    fn delay_long(&mut self, cycles: u64) {
+        let max = u64::from(self.bit_mode().max_value());
+
+        let mut remaining = cycles;
+        while remaining > 0 {
+            let cycles = if remaining > max { max } else { remaining };
+            self.delay(cycles as u32);
+            remaining -= cycles;
+        }
+
+        // A periodic timer would keep running
+        self.0.tasks_stop.write(|w| unsafe { w.bits(1) });
+    }
+
+    /// Start the timer for a raw number of cycles
     ///
     /// The timer will run for the given number of cycles, then it will stop and
-    /// reset.
-    fn start<Time>(&mut self, cycles: Time)
-    where
-        Time: Into<Self::Time>,
-    {
+    /// reset. `cycles` is limited to the largest value that fits into the
+    /// configured bit width.
+    pub fn start_cycles(&mut self, cycles: u32) {
+        let max = self.bit_mode().max_value();
+        let cycles = if cycles > max { max } else { cycles };
+
         // If the following sequence of events occurs, the COMPARE event will be
         // set here:
         // 1. `start` is called.
@@ -181,9 +404,8 @@ where
         // Configure timer to trigger EVENTS_COMPARE when given number of cycles
         // is reached.
         self.0.cc[0].write(|w|
-            // The timer mode was set to 32 bits above, so all possible values
-            // of `cycles` are valid.
-            unsafe { w.cc().bits(cycles.into()) });
+            // `cycles` was limited to the bit width above.
+            unsafe { w.cc().bits(cycles) });
 
         // Clear the counter value
         self.0.tasks_clear.write(|w| unsafe { w.bits(1) });
@@ -191,6 +413,31 @@ where
         // Start the timer
         self.0.tasks_start.write(|w| unsafe { w.bits(1) });
     }
+}
+
+impl<T, U> timer::CountDown for Timer<T, U>
+where
+    T: Instance,
+{
+    type Time = MicroSeconds;
+
+    /// Start the timer
+    ///
+    /// The timer will run for the given duration, then it will stop and
+    /// reset. Panics if the duration doesn't fit into the configured bit
+    /// width at the configured frequency.
+    fn start<Time>(&mut self, duration: Time)
+    where
+        Time: Into<Self::Time>,
+    {
+        let MicroSeconds(us) = duration.into();
+        let Hertz(frequency) = self.frequency();
+
+        let cycles = u64::from(us) * u64::from(frequency) / 1_000_000;
+        assert!(cycles <= u64::from(self.bit_mode().max_value()));
+
+        self.start_cycles(cycles as u32);
+    }
 
     /// Wait for the timer to stop
     ///
@@ -232,11 +479,526 @@ where
     T: Instance,
 {}
 
+impl<T, U> DelayMs<u32> for Timer<T, U>
+where
+    T: Instance,
+{
+    /// Block for the given number of milliseconds
+    ///
+    /// The delay is rounded up to whole timer cycles. The CPU sleeps in WFE
+    /// until it is over. Cancels any running countdown, as both use CC[0].
+    fn delay_ms(&mut self, ms: u32) {
+        let Hertz(frequency) = self.frequency();
+        self.delay_long((u64::from(ms) * u64::from(frequency) + 999) / 1_000);
+    }
+}
+
+impl<T, U> DelayMs<u16> for Timer<T, U>
+where
+    T: Instance,
+{
+    fn delay_ms(&mut self, ms: u16) {
+        self.delay_ms(u32::from(ms))
+    }
+}
+
+impl<T, U> DelayMs<u8> for Timer<T, U>
+where
+    T: Instance,
+{
+    fn delay_ms(&mut self, ms: u8) {
+        self.delay_ms(u32::from(ms))
+    }
+}
+
+impl<T, U> DelayUs<u32> for Timer<T, U>
+where
+    T: Instance,
+{
+    /// Block for the given number of microseconds
+    ///
+    /// The delay is rounded up to whole timer cycles. The CPU sleeps in WFE
+    /// until it is over. Cancels any running countdown, as both use CC[0].
+    fn delay_us(&mut self, us: u32) {
+        let Hertz(frequency) = self.frequency();
+        self.delay_long((u64::from(us) * u64::from(frequency) + 999_999) / 1_000_000);
+    }
+}
+
+impl<T, U> DelayUs<u16> for Timer<T, U>
+where
+    T: Instance,
+{
+    fn delay_us(&mut self, us: u16) {
+        self.delay_us(u32::from(us))
+    }
+}
+
+impl<T, U> DelayUs<u8> for Timer<T, U>
+where
+    T: Instance,
+{
+    fn delay_us(&mut self, us: u8) {
+        self.delay_us(u32::from(us))
+    }
+}
+
+
+/// A TIMER instance in counter mode
+///
+/// The counter is incremented by the COUNT task, which is usually triggered
+/// through PPI, e.g. from a GPIOTE event to count external pulses.
+///
+/// CC[1] is used to grab the current value of the counter at a given instant.
+/// The remaining channels are available through `cc2` to `cc5`, e.g. to get
+/// an event once a number of pulses has been counted.
+pub struct Counter<T>(T);
+
+impl<T> Counter<T>
+where
+    T: Instance,
+{
+    /// Start counting
+    pub fn start(&mut self) {
+        self.0.tasks_start.write(|w| unsafe { w.bits(1) });
+    }
+
+    /// Stop counting
+    ///
+    /// COUNT tasks are ignored while the counter is stopped.
+    pub fn stop(&mut self) {
+        self.0.tasks_stop.write(|w| unsafe { w.bits(1) });
+    }
+
+    /// Reset the counter to zero
+    pub fn clear(&mut self) {
+        self.0.tasks_clear.write(|w| unsafe { w.bits(1) });
+    }
+
+    /// Increment the counter from software
+    pub fn count(&mut self) {
+        self.0.tasks_count.write(|w| unsafe { w.bits(1) });
+    }
+
+    /// Return the current value of the counter, by capturing to CC[1].
+    pub fn read(&self) -> u32 {
+        self.0.tasks_capture[1].write(|w| unsafe { w.bits(1) });
+        self.0.cc[1].read().bits()
+    }
+
+    /// Capture/compare channel 2
+    pub fn cc2(&mut self) -> CcChannel<T> {
+        CcChannel::new(&self.0, 2)
+    }
+
+    /// Capture/compare channel 3
+    pub fn cc3(&mut self) -> CcChannel<T> {
+        CcChannel::new(&self.0, 3)
+    }
+
+    /// Capture/compare channel 4
+    pub fn cc4(&mut self) -> CcChannel<T>
+    where
+        T: SixChannelInstance,
+    {
+        CcChannel::new(&self.0, 4)
+    }
+
+    /// Capture/compare channel 5
+    pub fn cc5(&mut self) -> CcChannel<T>
+    where
+        T: SixChannelInstance,
+    {
+        CcChannel::new(&self.0, 5)
+    }
+
+    /// Address of the COUNT task, for use as a PPI task endpoint
+    pub fn tasks_count_address(&self) -> u32 {
+        &self.0.tasks_count as *const _ as u32
+    }
+
+    /// Return the raw interface to the underlying timer peripheral
+    pub fn free(self) -> T {
+        self.0
+    }
+}
+
+
+/// A capture/compare channel of a TIMER instance
+///
+/// Obtained from `Timer::cc2` and friends. CC[0] and CC[1] are used by
+/// `Timer` itself and therefore not available.
+pub struct CcChannel<'a, T> {
+    timer: &'a T,
+    index: usize,
+}
+
+impl<'a, T> CcChannel<'a, T>
+where
+    T: Instance,
+{
+    fn new(timer: &'a T, index: usize) -> Self {
+        CcChannel { timer, index }
+    }
+
+    /// Set the value that triggers this channel's COMPARE event
+    pub fn set_compare(&mut self, value: u32) {
+        self.timer.events_compare[self.index].reset();
+        self.timer.cc[self.index].write(|w| unsafe { w.cc().bits(value) });
+    }
+
+    /// Select whether the COMPARE event clears and/or stops the timer
+    pub fn set_shortcuts(&mut self, clear: bool, stop: bool) {
+        let clear_mask = 1 << self.index;
+        let stop_mask = 1 << (self.index + 8);
+
+        self.timer.shorts.modify(|r, w| {
+            let mut bits = r.bits() & !(clear_mask | stop_mask);
+            if clear {
+                bits |= clear_mask;
+            }
+            if stop {
+                bits |= stop_mask;
+            }
+            unsafe { w.bits(bits) }
+        });
+    }
+
+    /// Capture the current counter value and return it
+    pub fn capture(&mut self) -> u32 {
+        self.timer.tasks_capture[self.index].write(|w| unsafe { w.bits(1) });
+        self.read()
+    }
+
+    /// Return the channel's value, i.e. the compare value or the most
+    /// recently captured counter value
+    pub fn read(&self) -> u32 {
+        self.timer.cc[self.index].read().bits()
+    }
+
+    /// Whether the COMPARE event has occurred
+    pub fn is_compare_triggered(&self) -> bool {
+        self.timer.events_compare[self.index].read().bits() != 0
+    }
+
+    /// Clear the COMPARE event
+    pub fn reset_compare_event(&mut self) {
+        self.timer.events_compare[self.index].reset();
+    }
+
+    /// Enables the COMPARE interrupt for this channel
+    ///
+    /// If access to the NVIC is not provided, the interrupt must ALSO be enabled
+    /// there outside of this function (e.g. manually call `nvic.enable`, or through
+    /// the use of RTFM).
+    pub fn enable_interrupt(&mut self, nvic: Option<&mut NVIC>) {
+        self.timer
+            .intenset
+            .write(|w| unsafe { w.bits(1 << (self.index + 16)) });
+
+        if let Some(_nvic) = nvic {
+            unsafe { NVIC::unmask(T::INTERRUPT) };
+        }
+    }
+
+    /// Disables the COMPARE interrupt for this channel
+    ///
+    /// The interrupt is not masked in the NVIC, as the timer's other channels
+    /// may still be using it.
+    pub fn disable_interrupt(&mut self) {
+        self.timer
+            .intenclr
+            .write(|w| unsafe { w.bits(1 << (self.index + 16)) });
+    }
+
+    /// Address of the CAPTURE task, for use as a PPI task endpoint
+    pub fn tasks_capture_address(&self) -> u32 {
+        &self.timer.tasks_capture[self.index] as *const _ as u32
+    }
+
+    /// Address of the COMPARE event, for use as a PPI event endpoint
+    pub fn events_compare_address(&self) -> u32 {
+        &self.timer.events_compare[self.index] as *const _ as u32
+    }
+}
+
+
+/// A free-running 64 bit microsecond clock
+///
+/// The TIMER runs at 1 MHz in 32 bit mode and is never stopped. Its COMPARE
+/// interrupt fires twice per counter period (every 35.8 minutes) to extend
+/// the counter to 64 bits, so the interrupt must be unmasked in the NVIC and
+/// `MonotonicTimer::<T>::on_interrupt` must be called from its handler. With
+/// the `rtfm` feature enabled, this type can be used as the `monotonic` of an
+/// RTFM application.
+///
+/// CC[0] and CC[1] are used for the overflow extension and CC[2] is used to
+/// grab the current value of the counter.
+pub struct MonotonicTimer<T>(T);
+
+impl<T> MonotonicTimer<T>
+where
+    T: Instance,
+{
+    /// Start the clock at zero
+    pub fn new(timer: T) -> Self {
+        timer.tasks_stop.write(|w| unsafe { w.bits(1) });
+        timer.shorts.reset();
+        timer.mode.write(|w| w.mode().timer());
+        timer.prescaler.write(
+            |w| unsafe { w.prescaler().bits(4) }, // 1 MHz
+        );
+        timer.bitmode.write(|w| w.bitmode()._32bit());
+
+        // Fire halfway through the counter period and on overflow
+        timer.cc[0].write(|w| unsafe { w.cc().bits(0x8000_0000) });
+        timer.cc[1].write(|w| unsafe { w.cc().bits(0) });
+        timer
+            .intenset
+            .write(|w| w.compare0().set().compare1().set());
+
+        unsafe { Self::reset() };
+        timer.tasks_start.write(|w| unsafe { w.bits(1) });
+
+        MonotonicTimer(timer)
+    }
+
+    /// Return the current time
+    ///
+    /// Only meaningful while a `MonotonicTimer` exists for `T`.
+    pub fn now() -> Instant {
+        let timer = T::regs();
+
+        // The period may be stale by one half period while its interrupt is
+        // pending, which `calc_now` accounts for. Reading it before the
+        // counter ensures it's never ahead of it.
+        let period = T::monotonic_period().load(Ordering::Relaxed);
+        compiler_fence(Ordering::SeqCst);
+
+        timer.tasks_capture[2].write(|w| unsafe { w.bits(1) });
+        let counter = timer.cc[2].read().bits();
+
+        Instant(calc_now(period, counter))
+    }
+
+    /// Reset the clock to zero
+    ///
+    /// # Safety
+    ///
+    /// Accesses the TIMER without owning it. Must not race with `now` or
+    /// `on_interrupt`, e.g. by being called with interrupts disabled.
+    pub unsafe fn reset() {
+        let timer = T::regs();
+
+        timer.tasks_clear.write(|w| w.bits(1));
+        timer.events_compare[0].reset();
+        timer.events_compare[1].reset();
+        T::monotonic_period().store(0, Ordering::Relaxed);
+    }
+
+    /// Handle the TIMER interrupt
+    ///
+    /// Must be called from the interrupt handler of `T`.
+    pub fn on_interrupt() {
+        let timer = T::regs();
+
+        for event in &timer.events_compare[..2] {
+            if event.read().bits() != 0 {
+                event.reset();
+                T::monotonic_period().fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+
+    /// Stop the clock and return the raw interface to the underlying timer
+    /// peripheral
+    pub fn free(self) -> T {
+        self.0.tasks_stop.write(|w| unsafe { w.bits(1) });
+        self.0
+            .intenclr
+            .write(|w| w.compare0().clear().compare1().clear());
+        self.0
+    }
+}
+
+/// Combine the number of half counter periods with the counter value
+///
+/// Every other half period starts at 2^31, so the MSB of the counter has to
+/// match the parity of `period`. If it doesn't, the interrupt incrementing
+/// `period` is pending and the XOR accounts for the missing half period.
+fn calc_now(period: u32, counter: u32) -> u64 {
+    (u64::from(period) << 31) + u64::from(counter ^ ((period & 1) << 31))
+}
+
+#[cfg(feature = "rtfm")]
+impl<T> rtfm::Monotonic for MonotonicTimer<T>
+where
+    T: Instance,
+{
+    type Instant = Instant;
+
+    fn ratio() -> rtfm::Fraction {
+        // SysTick runs at the 64 MHz core clock
+        rtfm::Fraction {
+            numerator: 64,
+            denominator: 1,
+        }
+    }
+
+    fn now() -> Instant {
+        Self::now()
+    }
+
+    unsafe fn reset() {
+        Self::reset()
+    }
+
+    fn zero() -> Instant {
+        Instant::zero()
+    }
+}
+
+
+/// A point in time of a `MonotonicTimer`, in microseconds since it started
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Instant(u64);
+
+impl Instant {
+    pub const fn zero() -> Self {
+        Instant(0)
+    }
+
+    /// Microseconds since the clock started
+    pub fn as_micros(self) -> u64 {
+        self.0
+    }
+
+    /// The time that passed from `earlier` to `self`
+    ///
+    /// Panics if `earlier` is later than `self`.
+    pub fn duration_since(self, earlier: Instant) -> Duration {
+        Duration(self.0 - earlier.0)
+    }
+}
+
+impl Add<Duration> for Instant {
+    type Output = Instant;
+
+    fn add(self, rhs: Duration) -> Instant {
+        Instant(self.0 + rhs.0)
+    }
+}
+
+impl AddAssign<Duration> for Instant {
+    fn add_assign(&mut self, rhs: Duration) {
+        self.0 += rhs.0;
+    }
+}
+
+impl Sub<Duration> for Instant {
+    type Output = Instant;
+
+    fn sub(self, rhs: Duration) -> Instant {
+        Instant(self.0 - rhs.0)
+    }
+}
+
+impl SubAssign<Duration> for Instant {
+    fn sub_assign(&mut self, rhs: Duration) {
+        self.0 -= rhs.0;
+    }
+}
+
+impl Sub for Instant {
+    type Output = Duration;
+
+    fn sub(self, rhs: Instant) -> Duration {
+        self.duration_since(rhs)
+    }
+}
+
+/// A span of time, in microseconds
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Duration(u64);
+
+impl Duration {
+    pub const fn from_micros(micros: u64) -> Self {
+        Duration(micros)
+    }
+
+    pub const fn from_millis(millis: u64) -> Self {
+        Duration(millis * 1_000)
+    }
+
+    pub const fn from_secs(secs: u64) -> Self {
+        Duration(secs * 1_000_000)
+    }
+
+    pub fn as_micros(self) -> u64 {
+        self.0
+    }
+
+    pub fn as_millis(self) -> u64 {
+        self.0 / 1_000
+    }
+}
+
+impl Add for Duration {
+    type Output = Duration;
+
+    fn add(self, rhs: Duration) -> Duration {
+        Duration(self.0 + rhs.0)
+    }
+}
+
+impl Sub for Duration {
+    type Output = Duration;
+
+    fn sub(self, rhs: Duration) -> Duration {
+        Duration(self.0 - rhs.0)
+    }
+}
+
+impl From<MicroSeconds> for Duration {
+    fn from(t: MicroSeconds) -> Duration {
+        Duration::from_micros(u64::from(t.0))
+    }
+}
+
+impl From<MilliSeconds> for Duration {
+    fn from(t: MilliSeconds) -> Duration {
+        Duration::from_millis(u64::from(t.0))
+    }
+}
+
+impl From<Seconds> for Duration {
+    fn from(t: Seconds) -> Duration {
+        Duration::from_secs(u64::from(t.0))
+    }
+}
+
+/// Microseconds, as required by the RTFM timer queue
+impl TryFrom<Duration> for u32 {
+    type Error = TryFromIntError;
+
+    fn try_from(d: Duration) -> Result<u32, TryFromIntError> {
+        u32::try_from(d.0)
+    }
+}
+
 
 /// Implemented by all `TIMER` instances
 pub trait Instance: Deref<Target = timer0::RegisterBlock> {
     /// This interrupt associated with this RTC instance
     const INTERRUPT: Interrupt;
+
+    /// Register block, for `MonotonicTimer`'s functions that don't take `self`
+    #[doc(hidden)]
+    fn regs() -> &'static timer0::RegisterBlock;
+
+    /// Half periods counted by the `MonotonicTimer` of this instance
+    #[doc(hidden)]
+    fn monotonic_period() -> &'static AtomicU32;
 }
 
 macro_rules! impl_instance {
@@ -244,6 +1006,16 @@ macro_rules! impl_instance {
         $(
             impl Instance for $name {
                 const INTERRUPT: Interrupt = Interrupt::$name;
+
+                fn regs() -> &'static timer0::RegisterBlock {
+                    // Only used by `MonotonicTimer`, which owns the instance
+                    unsafe { &*$name::ptr() }
+                }
+
+                fn monotonic_period() -> &'static AtomicU32 {
+                    static PERIOD: AtomicU32 = AtomicU32::new(0);
+                    &PERIOD
+                }
             }
         )*
     }
@@ -253,3 +1025,20 @@ impl_instance!(TIMER0, TIMER1, TIMER2,);
 
 #[cfg(any(feature = "52832", feature = "52840"))]
 impl_instance!(TIMER3, TIMER4,);
+
+
+/// Implemented by the `TIMER` instances that have six capture/compare
+/// channels
+pub trait SixChannelInstance: Instance {}
+
+#[cfg(any(feature = "52832", feature = "52840"))]
+impl SixChannelInstance for TIMER3 {}
+#[cfg(any(feature = "52832", feature = "52840"))]
+impl SixChannelInstance for TIMER4 {}
+
+#[cfg(feature = "9160")]
+impl SixChannelInstance for TIMER0 {}
+#[cfg(feature = "9160")]
+impl SixChannelInstance for TIMER1 {}
+#[cfg(feature = "9160")]
+impl SixChannelInstance for TIMER2 {}
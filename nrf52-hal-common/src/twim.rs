@@ -10,6 +10,7 @@ use core::sync::atomic::{compiler_fence, Ordering::SeqCst};
 #[cfg(feature="9160")]
 use crate::target::{
     twim0_ns as twim0,
+    Interrupt,
     P0_NS as P0,
     TWIM0_NS as TWIM0,
 };
@@ -17,6 +18,7 @@ use crate::target::{
 #[cfg(not(feature="9160"))]
 use crate::target::{
     twim0,
+    Interrupt,
     P0,
     TWIM0,
 };
@@ -31,6 +33,12 @@ use crate::gpio::{
 };
 
 use crate::target_constants::EASY_DMA_SIZE;
+use crate::wait::wait_for_event;
+
+#[cfg(feature = "async")]
+use crate::waker::{poll_fn, OnDrop};
+#[cfg(feature = "async")]
+use core::task::Poll;
 
 pub use twim0::frequency::FREQUENCY_A as Frequency;
 
@@ -97,6 +105,9 @@ impl<T> Twim<T> where T: Instance {
     ///
     /// The buffer must have a length of at most 255 bytes on the nRF52832
     /// and at most 65535 bytes on the nRF52840.
+    ///
+    /// The TWIM interrupt handler must not clear the LASTTX or STOPPED event
+    /// while this blocks, or it never returns.
     pub fn write(&mut self,
         address: u8,
         buffer:  &[u8],
@@ -142,7 +153,10 @@ impl<T> Twim<T> where T: Instance {
         );
 
         // Wait until write operation is about to end
-        while self.0.events_lasttx.read().bits() == 0 {}
+        let twim = &self.0;
+        wait_for_event(twim, T::INTERRUPT, 1 << 24, || {
+            twim.events_lasttx.read().bits() != 0
+        });
         self.0.events_lasttx.write(|w| w); // reset event
 
         // Stop read operation
@@ -152,7 +166,9 @@ impl<T> Twim<T> where T: Instance {
         );
 
         // Wait until write operation has ended
-        while self.0.events_stopped.read().bits() == 0 {}
+        wait_for_event(twim, T::INTERRUPT, 1 << 1, || {
+            twim.events_stopped.read().bits() != 0
+        });
         self.0.events_stopped.write(|w| w); // reset event
 
         // Conservative compiler fence to prevent optimizations that do not
@@ -171,6 +187,9 @@ impl<T> Twim<T> where T: Instance {
     ///
     /// The buffer must have a length of at most 255 bytes on the nRF52832
     /// and at most 65535 bytes on the nRF52840.
+    ///
+    /// The TWIM interrupt handler must not clear the LASTRX or STOPPED event
+    /// while this blocks, or it never returns.
     pub fn read(&mut self,
         address: u8,
         buffer:  &mut [u8],
@@ -218,7 +237,10 @@ impl<T> Twim<T> where T: Instance {
         );
 
         // Wait until read operation is about to end
-        while self.0.events_lastrx.read().bits() == 0 {}
+        let twim = &self.0;
+        wait_for_event(twim, T::INTERRUPT, 1 << 23, || {
+            twim.events_lastrx.read().bits() != 0
+        });
         self.0.events_lastrx.write(|w| w); // reset event
 
         // Stop read operation
@@ -228,7 +250,9 @@ impl<T> Twim<T> where T: Instance {
         );
 
         // Wait until read operation has ended
-        while self.0.events_stopped.read().bits() == 0 {}
+        wait_for_event(twim, T::INTERRUPT, 1 << 1, || {
+            twim.events_stopped.read().bits() != 0
+        });
         self.0.events_stopped.write(|w| w); // reset event
 
         // Conservative compiler fence to prevent optimizations that do not
@@ -247,12 +271,35 @@ impl<T> Twim<T> where T: Instance {
     /// triggering a stop condition between the two
     ///
     /// The buffer must have a length of at most 255 bytes.
+    ///
+    /// The TWIM interrupt handler must not clear the STOPPED event while this
+    /// blocks, or it never returns.
     pub fn write_then_read(&mut self,
         address: u8,
         wr_buffer:  &[u8],
         rd_buffer: &mut [u8],
     )
         -> Result<(), Error>
+    {
+        self.start_write_then_read(address, wr_buffer, rd_buffer)?;
+
+        // Wait until total operation has ended
+        let twim = &self.0;
+        wait_for_event(twim, T::INTERRUPT, 1 << 1, || {
+            twim.events_stopped.read().bits() != 0
+        });
+
+        self.finalize_write_then_read(wr_buffer, rd_buffer)
+    }
+
+    /// Start a write-then-read transaction by setting the control values
+    /// and triggering the write task
+    fn start_write_then_read(&mut self,
+        address: u8,
+        wr_buffer:  &[u8],
+        rd_buffer: &mut [u8],
+    )
+        -> Result<(), Error>
     {
         if wr_buffer.len() > EASY_DMA_SIZE {
             return Err(Error::TxBufferTooLong);
@@ -324,9 +371,16 @@ impl<T> Twim<T> where T: Instance {
             unsafe { w.bits(1) }
         );
 
-        // Wait until total operation has ended
-        while self.0.events_stopped.read().bits() == 0 {}
+        Ok(())
+    }
 
+    /// Finalize a write-then-read transaction once STOPPED has been signalled
+    fn finalize_write_then_read(&mut self,
+        wr_buffer:  &[u8],
+        rd_buffer: &[u8],
+    )
+        -> Result<(), Error>
+    {
         self.0.events_lasttx.write(|w| w); // reset event
         self.0.events_lastrx.write(|w| w); // reset event
         self.0.events_stopped.write(|w| w); // reset event
@@ -357,6 +411,69 @@ impl<T> Twim<T> where T: Instance {
     }
 }
 
+#[cfg(feature = "async")]
+impl<T> Twim<T> where T: Instance {
+    /// Asynchronous version of `write_then_read`
+    ///
+    /// Requires `on_interrupt` to be called from the TWIM interrupt handler.
+    /// Dropping the returned future stops the transaction.
+    pub async fn write_then_read_async(&mut self,
+        address: u8,
+        wr_buffer:  &[u8],
+        rd_buffer: &mut [u8],
+    )
+        -> Result<(), Error>
+    {
+        self.start_write_then_read(address, wr_buffer, rd_buffer)?;
+
+        let twim = &*self.0;
+        let on_drop = OnDrop::new(|| {
+            // The future was dropped before the transaction completed, so
+            // stop it before the buffers go away.
+            twim.intenclr.write(|w| w.stopped().clear());
+            twim.tasks_stop.write(|w| unsafe { w.bits(1) });
+
+            // STOPPED follows once the byte in progress is done. This spins
+            // for as long as a device stretches the clock, as the drop can't
+            // await the event.
+            while twim.events_stopped.read().bits() == 0 {}
+            twim.events_lasttx.write(|w| w);
+            twim.events_lastrx.write(|w| w);
+            twim.events_stopped.write(|w| w);
+            twim.shorts.write(|w| w);
+        });
+
+        poll_fn(|cx| {
+            T::waker().register(cx.waker());
+            twim.intenset.write(|w| w.stopped().set());
+
+            if twim.events_stopped.read().bits() != 0 {
+                Poll::Ready(())
+            } else {
+                Poll::Pending
+            }
+        })
+        .await;
+        on_drop.defuse();
+
+        self.finalize_write_then_read(wr_buffer, rd_buffer)
+    }
+}
+
+/// Interrupt handler for the `async` methods of `Twim<T>`
+///
+/// Must be called from the interrupt handler of the TWIM instance `T`.
+#[cfg(feature = "async")]
+pub fn on_interrupt<T: Instance>() {
+    let twim = T::regs();
+
+    if twim.events_stopped.read().bits() != 0 {
+        // The event itself is reset by the woken task
+        twim.intenclr.write(|w| w.stopped().clear());
+        T::waker().wake();
+    }
+}
+
 /// Implementation of embedded_hal::blocking::i2c Traits
 
 impl<T> embedded_hal::blocking::i2c::Write for Twim<T> where T: Instance {
@@ -405,9 +522,35 @@ pub enum Error {
 
 
 /// Implemented by all TWIM instances
-pub trait Instance: Deref<Target=twim0::RegisterBlock> {}
+pub trait Instance: Deref<Target=twim0::RegisterBlock> {
+    /// The interrupt associated with this TWIM instance
+    const INTERRUPT: Interrupt;
 
-impl Instance for TWIM0 {}
+    /// The waker of the task waiting on this instance
+    #[cfg(feature = "async")]
+    fn waker() -> &'static crate::waker::WakerRegistration;
+
+    /// The register block of this instance, used by `on_interrupt`
+    #[cfg(feature = "async")]
+    fn regs() -> &'static twim0::RegisterBlock;
+}
+
+impl Instance for TWIM0 {
+    #[cfg(feature = "52810")]
+    const INTERRUPT: Interrupt = Interrupt::TWIM0_TWIS0_TWI0;
+    #[cfg(any(feature = "52832", feature = "52840"))]
+    const INTERRUPT: Interrupt = Interrupt::SPIM0_SPIS0_TWIM0_TWIS0_SPI0_TWI0;
+    #[cfg(feature = "9160")]
+    const INTERRUPT: Interrupt = Interrupt::UARTE0_SPIM0_SPIS0_TWIM0_TWIS0;
+
+    #[cfg(feature = "async")]
+    impl_async_instance!(TWIM0, twim0::RegisterBlock);
+}
 
 #[cfg(any(feature = "52832", feature = "52840"))]
-impl Instance for TWIM1 {}
+impl Instance for TWIM1 {
+    const INTERRUPT: Interrupt = Interrupt::SPIM1_SPIS1_TWIM1_TWIS1_SPI1_TWI1;
+
+    #[cfg(feature = "async")]
+    impl_async_instance!(TWIM1, twim0::RegisterBlock);
+}
@@ -16,6 +16,7 @@ use crate::target::UARTE1;
 #[cfg(feature="9160")]
 use crate::target::{
     uarte0_ns as uarte0,
+    Interrupt,
     UARTE0_NS as UARTE0,
     UARTE1_NS as UARTE1,
 };
@@ -23,6 +24,7 @@ use crate::target::{
 #[cfg(not(feature="9160"))]
 use crate::target::{
     uarte0,
+    Interrupt,
     UARTE0,
 };
 
@@ -36,6 +38,13 @@ use crate::gpio::{
     Floating,
 };
 use crate::timer::{self, Timer};
+use crate::clocks::{ExternalOscillator, Frozen, HfxoRequest, HfxoRequester};
+use crate::wait::{wait_for_event, with_event_interrupts};
+
+#[cfg(feature = "async")]
+use crate::waker::{poll_fn, OnDrop};
+#[cfg(feature = "async")]
+use core::task::Poll;
 
 // Re-export SVD variants to allow user to directly set values
 pub use uarte0::{
@@ -52,10 +61,47 @@ pub use uarte0::{
 ///   are disabled before using `Uarte`. See product specification:
 ///     - nrf52832: Section 15.2
 ///     - nrf52840: Section 6.1.2
-pub struct Uarte<T>(T);
+pub struct Uarte<T>(T, Option<HfxoRequester>);
 
 impl<T> Uarte<T> where T: Instance {
-    pub fn new(uarte: T, mut pins: Pins, parity: Parity, baudrate: Baudrate) -> Self {
+    /// Create the interface
+    ///
+    /// Requires the external high frequency oscillator, as the internal one
+    /// is not accurate enough for reliable communication.
+    pub fn new<L, LSTAT>(
+        uarte: T,
+        pins: Pins,
+        parity: Parity,
+        baudrate: Baudrate,
+        _clocks: Frozen<ExternalOscillator, L, LSTAT>,
+    ) -> Self {
+        Uarte::init(uarte, pins, parity, baudrate, None)
+    }
+
+    /// Create the interface, requesting the external high frequency
+    /// oscillator only for the duration of each transfer
+    ///
+    /// Saves the current of a permanently running oscillator, at the cost of
+    /// waiting for it to start (0.36 ms typically) at the beginning of each
+    /// transfer. This includes the `async` transfers, which block until the
+    /// oscillator is running.
+    pub fn with_hfxo_requester(
+        uarte: T,
+        pins: Pins,
+        parity: Parity,
+        baudrate: Baudrate,
+        hfxo: HfxoRequester,
+    ) -> Self {
+        Uarte::init(uarte, pins, parity, baudrate, Some(hfxo))
+    }
+
+    fn init(
+        uarte: T,
+        mut pins: Pins,
+        parity: Parity,
+        baudrate: Baudrate,
+        hfxo: Option<HfxoRequester>,
+    ) -> Self {
         // Select pins
         uarte.psel.rxd.write(|w| {
             let w = unsafe { w.pin().bits(pins.rxd.pin) };
@@ -111,7 +157,13 @@ impl<T> Uarte<T> where T: Instance {
             w.baudrate().variant(baudrate)
         );
 
-        Uarte(uarte)
+        Uarte(uarte, hfxo)
+    }
+
+    /// Request the external high frequency oscillator, if this interface
+    /// wasn't created with it running already
+    fn request_hfxo(&self) -> Option<HfxoRequest> {
+        self.1.as_ref().map(HfxoRequester::request)
     }
 
     /// Write via UARTE
@@ -120,11 +172,30 @@ impl<T> Uarte<T> where T: Instance {
     ///
     /// The buffer must have a length of at most 255 bytes on the nRF52832
     /// and at most 65535 bytes on the nRF52840.
+    ///
+    /// The UARTE interrupt handler must not clear the ENDTX or TXSTOPPED
+    /// event while this blocks, or it never returns.
     pub fn write(&mut self,
         tx_buffer  : &[u8],
     )
         -> Result<(), Error>
     {
+        let _hfxo = self.request_hfxo();
+        self.start_write(tx_buffer)?;
+
+        // Wait for transmission to end
+        let uarte = &self.0;
+        wait_for_event(uarte, T::INTERRUPT, (1 << 8) | (1 << 22), || {
+            uarte.events_endtx.read().bits() != 0
+                || uarte.events_txstopped.read().bits() != 0
+        });
+
+        self.finalize_write()
+    }
+
+    /// Start a UARTE write transaction by setting the control
+    /// values and triggering a write task
+    fn start_write(&mut self, tx_buffer: &[u8]) -> Result<(), Error> {
         if tx_buffer.len() > EASY_DMA_SIZE {
             return Err(Error::TxBufferTooLong);
         }
@@ -140,8 +211,8 @@ impl<T> Uarte<T> where T: Instance {
         compiler_fence(SeqCst);
 
         // Reset the events.
-        self.0.events_endtx.reset();
-        self.0.events_txstopped.reset();
+        self.0.events_endtx.write(|w| w);
+        self.0.events_txstopped.write(|w| w);
 
         // Set up the DMA write
         self.0.txd.ptr.write(|w|
@@ -167,23 +238,18 @@ impl<T> Uarte<T> where T: Instance {
             // `1` is a valid value to write to task registers.
             unsafe { w.bits(1) });
 
-        // Wait for transmission to end
-        let mut endtx;
-        let mut txstopped;
-        loop {
-            endtx = self.0.events_endtx.read().bits() != 0;
-            txstopped = self.0.events_txstopped.read().bits() != 0;
-            if endtx || txstopped {
-                break;
-            }
-        }
+        Ok(())
+    }
 
+    /// Finalize a UARTE write transaction once ENDTX or TXSTOPPED has been
+    /// signalled
+    fn finalize_write(&mut self) -> Result<(), Error> {
         // Conservative compiler fence to prevent optimizations that do not
         // take in to account actions by DMA. The fence has been placed here,
         // after all possible DMA actions have completed
         compiler_fence(SeqCst);
 
-        if txstopped {
+        if self.0.events_txstopped.read().bits() != 0 {
             return Err(Error::Transmit);
         }
 
@@ -202,15 +268,22 @@ impl<T> Uarte<T> where T: Instance {
     /// until the buffer is full.
     ///
     /// The buffer must have a length of at most 255 bytes
+    ///
+    /// The UARTE interrupt handler must not clear the ENDRX event while this
+    /// blocks, or it never returns.
     pub fn read(&mut self,
         rx_buffer  : &mut [u8],
     )
         -> Result<(), Error>
     {
+        let _hfxo = self.request_hfxo();
         self.start_read(rx_buffer)?;
 
         // Wait for transmission to end
-        while self.0.events_endrx.read().bits() == 0 {}
+        let uarte = &self.0;
+        wait_for_event(uarte, T::INTERRUPT, 1 << 4, || {
+            uarte.events_endrx.read().bits() != 0
+        });
 
         self.finalize_read();
 
@@ -230,11 +303,18 @@ impl<T> Uarte<T> where T: Instance {
     /// If the timeout occurs, an `Error::Timeout(n)` will be returned,
     /// where `n` is the number of bytes read successfully.
     ///
-    /// This method assumes the interrupt for the given timer is NOT enabled,
-    /// and in cases where a timeout does NOT occur, the timer will be left running
+    /// `cycles` counts at the timer's configured frequency. Timeouts that
+    /// don't fit into its bit width are waited for in several periods.
+    ///
+    /// In cases where a timeout does NOT occur, the timer will be left running
     /// until completion.
     ///
     /// The buffer must have a length of at most 255 bytes
+    ///
+    /// The CPU sleeps in WFE until ENDRX or the timer's COMPARE[0] event. The
+    /// UARTE interrupt handler must not clear the RXTO or ENDRX event, and the
+    /// timer's interrupt handler, if enabled, must not clear COMPARE[0] while
+    /// this blocks, or it never returns.
     pub fn read_timeout<I>(
         &mut self,
         rx_buffer: &mut [u8],
@@ -242,19 +322,38 @@ impl<T> Uarte<T> where T: Instance {
         cycles: u32
     ) -> Result<(), Error> where I: timer::Instance
     {
+        let _hfxo = self.request_hfxo();
         // Start the read
         self.start_read(rx_buffer)?;
 
-        // Start the timeout timer
-        timer.start(cycles);
+        // Start the timeout timer, for as much of the timeout as fits into its
+        // bit width
+        let max = timer.bit_mode().max_value();
+        let mut remaining = cycles;
+        let period = if remaining > max { max } else { remaining };
+        timer.start_cycles(period);
+        remaining -= period;
 
         // Wait for transmission to end
         let mut event_complete = false;
         let mut timeout_occured = false;
 
         loop {
+            let uarte = &self.0;
+            with_event_interrupts(uarte, T::INTERRUPT, 1 << 4, || {
+                timer.sleep_until_compare_or(|| uarte.events_endrx.read().bits() != 0)
+            });
+
             event_complete |= self.0.events_endrx.read().bits() != 0;
-            timeout_occured |= timer.wait().is_ok();
+            if timer.wait().is_ok() {
+                if remaining == 0 {
+                    timeout_occured = true;
+                } else {
+                    let period = if remaining > max { max } else { remaining };
+                    timer.start_cycles(period);
+                    remaining -= period;
+                }
+            }
             if event_complete || timeout_occured {
                 break;
             }
@@ -340,7 +439,10 @@ impl<T> Uarte<T> where T: Instance {
             unsafe { w.bits(1) });
 
         // Wait for the reception to have stopped
-        while self.0.events_rxto.read().bits() == 0 {}
+        let uarte = &self.0;
+        wait_for_event(uarte, T::INTERRUPT, 1 << 17, || {
+            uarte.events_rxto.read().bits() != 0
+        });
 
         // Reset the event flag
         self.0.events_rxto.write(|w| w);
@@ -350,7 +452,9 @@ impl<T> Uarte<T> where T: Instance {
             unsafe { w.bits(1) });
 
         // Wait for the flush to complete.
-        while self.0.events_endrx.read().bits() == 0 {}
+        wait_for_event(uarte, T::INTERRUPT, 1 << 4, || {
+            uarte.events_endrx.read().bits() != 0
+        });
 
         // The event flag itself is later reset by `finalize_read`.
     }
@@ -361,8 +465,125 @@ impl<T> Uarte<T> where T: Instance {
     }
 }
 
+#[cfg(feature = "async")]
+impl<T> Uarte<T> where T: Instance {
+    /// Asynchronous version of `write`
+    ///
+    /// Requires `on_interrupt` to be called from the UARTE interrupt handler.
+    /// Dropping the returned future stops the transmission.
+    pub async fn write_async(&mut self,
+        tx_buffer  : &[u8],
+    )
+        -> Result<(), Error>
+    {
+        let _hfxo = self.request_hfxo();
+        self.start_write(tx_buffer)?;
+
+        let uarte = &*self.0;
+        let on_drop = OnDrop::new(|| {
+            // The future was dropped before the transmission completed, so
+            // stop it before the buffer goes away.
+            uarte.intenclr.write(|w| w.endtx().clear().txstopped().clear());
+            uarte.tasks_stoptx.write(|w| unsafe { w.bits(1) });
+
+            // STOPTX always generates TXSTOPPED, within a few cycles, so this
+            // doesn't spin for long. The drop can't await the event.
+            while uarte.events_txstopped.read().bits() == 0 {}
+            uarte.events_txstopped.write(|w| w);
+            uarte.events_endtx.write(|w| w);
+        });
+
+        poll_fn(|cx| {
+            T::waker().register(cx.waker());
+            uarte.intenset.write(|w| w.endtx().set().txstopped().set());
+
+            if uarte.events_endtx.read().bits() != 0
+                || uarte.events_txstopped.read().bits() != 0
+            {
+                Poll::Ready(())
+            } else {
+                Poll::Pending
+            }
+        })
+        .await;
+        on_drop.defuse();
+
+        self.finalize_write()
+    }
+
+    /// Asynchronous version of `read`
+    ///
+    /// Requires `on_interrupt` to be called from the UARTE interrupt handler.
+    /// Dropping the returned future stops the reception.
+    pub async fn read_async(&mut self,
+        rx_buffer  : &mut [u8],
+    )
+        -> Result<(), Error>
+    {
+        let _hfxo = self.request_hfxo();
+        self.start_read(rx_buffer)?;
+
+        let uarte = &*self.0;
+        let on_drop = OnDrop::new(|| {
+            // The future was dropped before the reception completed, so
+            // stop it before the buffer goes away.
+            uarte.intenclr.write(|w| w.endrx().clear());
+            uarte.tasks_stoprx.write(|w| unsafe { w.bits(1) });
+
+            // STOPRX always generates RXTO, after at most the time it takes
+            // to receive the few bytes still in flight, so this doesn't spin
+            // for long. The drop can't await the event.
+            while uarte.events_rxto.read().bits() == 0 {}
+            uarte.events_rxto.write(|w| w);
+            uarte.events_endrx.write(|w| w);
+        });
+
+        poll_fn(|cx| {
+            T::waker().register(cx.waker());
+            uarte.intenset.write(|w| w.endrx().set());
+
+            if uarte.events_endrx.read().bits() != 0 {
+                Poll::Ready(())
+            } else {
+                Poll::Pending
+            }
+        })
+        .await;
+        on_drop.defuse();
+
+        self.finalize_read();
+
+        if self.0.rxd.amount.read().bits() != rx_buffer.len() as u32 {
+            return Err(Error::Receive);
+        }
+
+        Ok(())
+    }
+}
+
+/// Interrupt handler for the `async` methods of `Uarte<T>`
+///
+/// Must be called from the interrupt handler of the UARTE instance `T`.
+#[cfg(feature = "async")]
+pub fn on_interrupt<T: Instance>() {
+    let uarte = T::regs();
+
+    // The events themselves are reset by the woken task
+    if uarte.events_endtx.read().bits() != 0 || uarte.events_txstopped.read().bits() != 0 {
+        uarte.intenclr.write(|w| w.endtx().clear().txstopped().clear());
+        T::waker().wake();
+    }
+    if uarte.events_endrx.read().bits() != 0 {
+        uarte.intenclr.write(|w| w.endrx().clear());
+        T::waker().wake();
+    }
+}
+
 impl<T> fmt::Write for Uarte<T> where T: Instance {
     fn write_str(&mut self, s: &str) -> fmt::Result {
+        // Keep the oscillator running across the blocks below
+        let _hfxo = self.request_hfxo();
+
         // Copy all data into an on-stack buffer so we never try to EasyDMA from
         // flash
         let buf = &mut [0; 16][..];
@@ -394,9 +615,36 @@ pub enum Error {
 }
 
 
-pub trait Instance: Deref<Target = uarte0::RegisterBlock> {}
+pub trait Instance: Deref<Target = uarte0::RegisterBlock> {
+    /// The interrupt associated with this UARTE instance
+    const INTERRUPT: Interrupt;
+
+    /// The waker of the task waiting on this instance
+    #[cfg(feature = "async")]
+    fn waker() -> &'static crate::waker::WakerRegistration;
 
-impl Instance for UARTE0 {}
+    /// The register block of this instance, used by `on_interrupt`
+    #[cfg(feature = "async")]
+    fn regs() -> &'static uarte0::RegisterBlock;
+}
+
+impl Instance for UARTE0 {
+    #[cfg(not(feature = "9160"))]
+    const INTERRUPT: Interrupt = Interrupt::UARTE0_UART0;
+    #[cfg(feature = "9160")]
+    const INTERRUPT: Interrupt = Interrupt::UARTE0_SPIM0_SPIS0_TWIM0_TWIS0;
+
+    #[cfg(feature = "async")]
+    impl_async_instance!(UARTE0, uarte0::RegisterBlock);
+}
 
 #[cfg(any(feature="52840", feature="9160"))]
-impl Instance for UARTE1 {}
+impl Instance for UARTE1 {
+    #[cfg(feature = "52840")]
+    const INTERRUPT: Interrupt = Interrupt::UARTE1;
+    #[cfg(feature = "9160")]
+    const INTERRUPT: Interrupt = Interrupt::UARTE1_SPIM1_SPIS1_TWIM1_TWIS1;
+
+    #[cfg(feature = "async")]
+    impl_async_instance!(UARTE1, uarte0::RegisterBlock);
+}
@@ -0,0 +1,105 @@
+//! Low-power waiting for peripheral events
+//!
+//! The blocking drivers don't spin while the peripheral is busy. They enable
+//! the interrupt of the event they are waiting for, so that the event makes
+//! the interrupt pending, and sleep in WFE. With SEVONPEND set, a pending
+//! interrupt wakes the CPU even if it is masked in the NVIC. SEVONPEND is
+//! only set for the duration of the wait.
+//!
+//! As the events are checked after waking up, an interrupt handler that runs
+//! for the peripheral during a blocking call must not clear the event the
+//! call is waiting for, or the call never returns.
+
+use core::ops::Deref;
+use core::ptr;
+
+use cortex_m::asm;
+use cortex_m::peripheral::SCB;
+
+use crate::target::{Interrupt, NVIC};
+
+/// SEVONPEND bit of the System Control Register
+const SCB_SCR_SEVONPEND: u32 = 1 << 4;
+
+/// Offsets of INTENSET and INTENCLR, which are the same for all peripherals
+const INTENSET_OFFSET: usize = 0x304;
+const INTENCLR_OFFSET: usize = 0x308;
+
+/// Sleep until `done` returns `true`
+///
+/// `inten` is the mask of the INTENSET bits of the events checked by `done`.
+/// Their interrupts are enabled for the duration of the wait and restored to
+/// their previous state afterwards. The events themselves are left for the
+/// caller to clear.
+///
+/// If `interrupt` is unmasked in the NVIC, its handler runs when one of the
+/// events occurs. It must not clear the events, or the wait won't end.
+pub(crate) fn wait_for_event<P>(
+    periph: &P,
+    interrupt: Interrupt,
+    inten: u32,
+    done: impl FnMut() -> bool,
+) where
+    P: Deref,
+    P::Target: Sized,
+{
+    with_event_interrupts(periph, interrupt, inten, || sleep_until(done));
+}
+
+/// Run `f` with the interrupts of the events in `inten` enabled
+///
+/// This lets `sleep_until` wait for events of more than one peripheral, by
+/// nesting calls for each of them. The interrupts are restored to their
+/// previous state afterwards.
+pub(crate) fn with_event_interrupts<P, R>(
+    periph: &P,
+    interrupt: Interrupt,
+    inten: u32,
+    f: impl FnOnce() -> R,
+) -> R
+where
+    P: Deref,
+    P::Target: Sized,
+{
+    let base = &**periph as *const P::Target as usize;
+    let intenset = (base + INTENSET_OFFSET) as *mut u32;
+    let intenclr = (base + INTENCLR_OFFSET) as *mut u32;
+
+    let result = unsafe {
+        let already_enabled = ptr::read_volatile(intenset) & inten;
+        ptr::write_volatile(intenset, inten);
+
+        let result = f();
+
+        ptr::write_volatile(intenclr, inten & !already_enabled);
+        result
+    };
+
+    // A masked interrupt would otherwise stay pending and wake every later
+    // WFE right away
+    if !NVIC::is_enabled(interrupt) {
+        NVIC::unpend(interrupt);
+    }
+
+    result
+}
+
+/// Sleep in WFE until `done` returns `true`
+///
+/// Only returns if one of the events checked by `done` has its interrupt
+/// enabled, see `with_event_interrupts`.
+pub(crate) fn sleep_until(mut done: impl FnMut() -> bool) {
+    unsafe {
+        // Any interrupt becoming pending sends an event, even if it is masked
+        let scr = (*SCB::ptr()).scr.read();
+        (*SCB::ptr()).scr.write(scr | SCB_SCR_SEVONPEND);
+
+        // If the event happens between the check and WFE, the event register
+        // is set and WFE returns immediately.
+        while !done() {
+            asm::wfe();
+        }
+
+        (*SCB::ptr()).scr.write(scr);
+    }
+}
@@ -0,0 +1,132 @@
+//! Support code for the `async` methods of the DMA drivers
+//!
+//! Only available with the `async` feature enabled.
+//!
+//! The `async` methods enable the interrupt of the peripheral event they are
+//! waiting for and register the waker of the current task for the peripheral
+//! instance. The application must unmask the interrupt in the NVIC and call
+//! the driver's `on_interrupt` function from the interrupt handler, which
+//! disables the event's interrupt again and wakes the task:
+//!
+//! ``` ignore
+//! #[interrupt]
+//! fn SPIM0_SPIS0_TWIM0_TWIS0_SPI0_TWI0() {
+//!     spim::on_interrupt::<SPIM0>();
+//! }
+//! ```
+//!
+//! Dropping one of the returned futures before it has completed stops the
+//! DMA transfer, so the buffers it borrows are never accessed afterwards.
+//! Note that this relies on the future actually being dropped; leaking it
+//! with `core::mem::forget` leaves EasyDMA running.
+
+use core::cell::RefCell;
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll, Waker};
+
+use cortex_m::interrupt;
+
+/// Storage for the waker of the task waiting on a peripheral instance
+pub struct WakerRegistration {
+    waker: RefCell<Option<Waker>>,
+}
+
+// All accesses to `waker` happen within a critical section.
+unsafe impl Sync for WakerRegistration {}
+
+impl WakerRegistration {
+    pub const fn new() -> Self {
+        WakerRegistration {
+            waker: RefCell::new(None),
+        }
+    }
+
+    /// Register the waker to be woken by the next call to `wake`
+    pub fn register(&self, waker: &Waker) {
+        interrupt::free(|_| {
+            let mut slot = self.waker.borrow_mut();
+            match *slot {
+                Some(ref w) if w.will_wake(waker) => {}
+                _ => *slot = Some(waker.clone()),
+            }
+        })
+    }
+
+    /// Wake the registered waker, if any
+    pub fn wake(&self) {
+        if let Some(waker) = interrupt::free(|_| self.waker.borrow_mut().take()) {
+            waker.wake();
+        }
+    }
+}
+
+/// Implements the `async` support methods of a driver's `Instance` trait
+///
+/// Drivers of peripherals with a single instance invoke it in an inherent
+/// `impl` block of the driver instead.
+macro_rules! impl_async_instance {
+    ($name:ident, $regs:ty) => {
+        fn waker() -> &'static crate::waker::WakerRegistration {
+            static WAKER: crate::waker::WakerRegistration =
+                crate::waker::WakerRegistration::new();
+            &WAKER
+        }
+
+        fn regs() -> &'static $regs {
+            // The register block is only used to check and disable events
+            // from the interrupt handler, which doesn't interfere with the
+            // driver owning the instance.
+            unsafe { &*$name::ptr() }
+        }
+    };
+}
+
+/// A future that calls a closure until it returns `Poll::Ready`
+pub(crate) struct PollFn<F> {
+    f: F,
+}
+
+pub(crate) fn poll_fn<T, F>(f: F) -> PollFn<F>
+where
+    F: FnMut(&mut Context) -> Poll<T> + Unpin,
+{
+    PollFn { f }
+}
+
+impl<T, F> Future for PollFn<F>
+where
+    F: FnMut(&mut Context) -> Poll<T> + Unpin,
+{
+    type Output = T;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<T> {
+        (self.get_mut().f)(cx)
+    }
+}
+
+/// Runs a closure when dropped, unless it was defused
+///
+/// Used to stop a DMA transfer if the future waiting for it is dropped.
+pub(crate) struct OnDrop<F: FnOnce()> {
+    f: Option<F>,
+}
+
+impl<F: FnOnce()> OnDrop<F> {
+    pub fn new(f: F) -> Self {
+        OnDrop { f: Some(f) }
+    }
+
+    /// Drop without running the closure
+    pub fn defuse(mut self) {
+        self.f = None;
+    }
+}
+
+impl<F: FnOnce()> Drop for OnDrop<F> {
+    fn drop(&mut self) {
+        if let Some(f) = self.f.take() {
+            f();
+        }
+    }
+}
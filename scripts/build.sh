@@ -7,12 +7,28 @@ set -e
 
 echo Building nrf9160-hal...
 cargo build --manifest-path nrf9160-hal/Cargo.toml --target thumbv8m.main-none-eabi
+echo Building nrf9160-hal with async...
+cargo build --manifest-path nrf9160-hal/Cargo.toml --target thumbv8m.main-none-eabi --features async
+echo Building nrf9160-hal with rtfm...
+cargo build --manifest-path nrf9160-hal/Cargo.toml --target thumbv8m.main-none-eabi --features rtfm
 echo Building nrf52810-hal...
 cargo build --manifest-path nrf52810-hal/Cargo.toml --target thumbv7em-none-eabi
+echo Building nrf52810-hal with async...
+cargo build --manifest-path nrf52810-hal/Cargo.toml --target thumbv7em-none-eabi --features async
+echo Building nrf52810-hal with rtfm...
+cargo build --manifest-path nrf52810-hal/Cargo.toml --target thumbv7em-none-eabi --features rtfm
 echo Building nrf52832-hal...
 cargo build --manifest-path nrf52832-hal/Cargo.toml
+echo Building nrf52832-hal with async...
+cargo build --manifest-path nrf52832-hal/Cargo.toml --features async
+echo Building nrf52832-hal with rtfm...
+cargo build --manifest-path nrf52832-hal/Cargo.toml --features rtfm
 echo Building nrf52840-hal...
 cargo build --manifest-path nrf52840-hal/Cargo.toml
+echo Building nrf52840-hal with async...
+cargo build --manifest-path nrf52840-hal/Cargo.toml --features async
+echo Building nrf52840-hal with rtfm...
+cargo build --manifest-path nrf52840-hal/Cargo.toml --features rtfm
 echo Building boards/adafruit_nrf52pro...
 cargo build --manifest-path boards/adafruit_nrf52pro/Cargo.toml --examples
 echo Building boards/adafruit-nrf52-bluefruit-le...
@@ -37,3 +53,5 @@ echo Building examples/twi-ssd1306...
 cargo build --manifest-path examples/twi-ssd1306/Cargo.toml
 echo Building examples/twi-ssd1306...
 cargo build --manifest-path examples/twi-ssd1306/Cargo.toml --no-default-features --features="52840" --target thumbv7em-none-eabi
+echo Building examples/saadc-limit-demo...
+cargo build --manifest-path examples/saadc-limit-demo/Cargo.toml